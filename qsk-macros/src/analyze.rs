@@ -8,12 +8,49 @@ use qsk_types::{LayerComposer, LayerRef, KeyCode, ControlCode};
 use crate::parse;
 use crate::parse::{Ast, LayerBody};
 
-const VALID_KEY_FUNCTIONS: [&'static str; 3] = ["TT", "TapToggle", "Exit"];
+const VALID_KEY_FUNCTIONS: [&'static str; 15] = [
+    "TT", "TapToggle", "MO", "Momentary", "TG", "Toggle", "TO", "Switch", "MT", "ModTap",
+    "MouseMove", "MouseButton", "MouseWheel", "TogglePassthrough", "Exit",
+];
+
+const LAYER_REF_KEY_FUNCTIONS: [&str; 8] = [
+    "TT", "TapToggle", "MO", "Momentary", "TG", "Toggle", "TO", "Switch",
+];
+
+// Key functions that take a layer ref as their only argument.
+fn single_layer_ref(parsed: &parse::KeyFunction) -> LayerRef {
+    let mut params = parsed.params.clone().0.into_iter();
+    let layer_ref = params
+        .next()
+        .unwrap_or_else(|| abort!(
+            // ../tests/fail/analyze/momentary-missing-layer-ref-argument.rs
+            parsed.name.0.span(),
+            "missing layer ref argument"
+        ))
+        .into();
+    if let Some(param) = params.next() {
+        abort!(param.span(), "unexpected argument");
+    }
+    layer_ref
+}
 
 impl From<parse::KeyFunctionParameter> for LayerRef {
     fn from(parsed: parse::KeyFunctionParameter) -> Self {
         match parsed {
-            parse::KeyFunctionParameter::StringParameter(ident) => LayerRef::ByName(ident.to_string()),
+            parse::KeyFunctionParameter::String(ident) => LayerRef::ByName(ident.to_string()),
+            parse::KeyFunctionParameter::Index(index) => {
+                match index.0.base10_parse::<usize>() {
+                    Ok(i) => LayerRef::ByIndex(i),
+                    Err(e) => abort!(
+                        index.span(),
+                        format!("invalid layer index: {}", e),
+                    ),
+                }
+            },
+            parse::KeyFunctionParameter::Negative(negative) => abort!(
+                negative.span(),
+                "invalid layer index: layer indices can't be negative",
+            ),
         }
     }
 }
@@ -21,7 +58,7 @@ impl From<parse::KeyFunctionParameter> for LayerRef {
 impl From<parse::KeyFunctionParameter> for KeyCode {
     fn from(parsed: parse::KeyFunctionParameter) -> Self {
         match parsed {
-            parse::KeyFunctionParameter::StringParameter(param) => {
+            parse::KeyFunctionParameter::String(param) => {
                 let mut kc_str = param.to_string();
                 if !kc_str.starts_with("KC_") {
                     kc_str = "KC_".to_owned() + &kc_str;
@@ -36,10 +73,48 @@ impl From<parse::KeyFunctionParameter> for KeyCode {
                     )},
                 }
             },
+            parse::KeyFunctionParameter::Index(index) => {
+                // ../tests/fail/analyze/layer-index-as-key-code.rs
+                abort!(
+                    index.span(),
+                    "expected key code, found layer index",
+                )
+            },
+            parse::KeyFunctionParameter::Negative(negative) => {
+                abort!(
+                    negative.span(),
+                    "expected key code, found integer",
+                )
+            },
         }
     }
 }
 
+// Integer arguments like the distances given to MouseMove.
+fn integer(param: parse::KeyFunctionParameter) -> i32 {
+    let (lit, sign) = match &param {
+        parse::KeyFunctionParameter::Index(index) => (&index.0, 1),
+        parse::KeyFunctionParameter::Negative(negative) => (&negative.0, -1),
+        parse::KeyFunctionParameter::String(_) => abort!(
+            param.span(),
+            "expected integer",
+        ),
+    };
+    match lit.base10_parse::<i32>() {
+        Ok(i) => sign * i,
+        Err(e) => abort!(
+            param.span(),
+            format!("invalid integer: {}", e),
+        ),
+    }
+}
+
+fn no_more_arguments(params: &mut impl Iterator<Item = parse::KeyFunctionParameter>) {
+    if let Some(param) = params.next() {
+        abort!(param.span(), "unexpected argument")
+    }
+}
+
 impl From<&parse::KeyFunction> for ControlCode {
     fn from(parsed: &parse::KeyFunction) -> Self {
         let mut params = parsed.params.clone().0.into_iter();
@@ -55,6 +130,10 @@ impl From<&parse::KeyFunction> for ControlCode {
                 }
                 ControlCode::Exit
             },
+            "TogglePassthrough" => {
+                no_more_arguments(&mut params);
+                ControlCode::TogglePassthrough
+            },
             "TT" | "TapToggle" => {
                 let layer_ref = params
                     .next()
@@ -82,6 +161,68 @@ impl From<&parse::KeyFunction> for ControlCode {
                 }
                 ControlCode::TapToggle(layer_ref, key)
             },
+            "MO" | "Momentary" => ControlCode::Momentary(single_layer_ref(parsed)),
+            "TG" | "Toggle" => ControlCode::Toggle(single_layer_ref(parsed)),
+            "TO" | "Switch" => ControlCode::Switch(single_layer_ref(parsed)),
+            "MT" | "ModTap" => {
+                let modifier = params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        parsed.name.0.span(),
+                        "missing modifier key code argument"
+                    ))
+                    .into();
+                let key = params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        // ../tests/fail/analyze/mod-tap-missing-keycode-argument.rs
+                        parsed.name.0.span(),
+                        "missing key code argument"
+                    ))
+                    .into();
+                if let Some(param) = params.next() {
+                    abort!(param.span(), "unexpected argument");
+                }
+                ControlCode::ModTap(modifier, key)
+            },
+            "MouseMove" => {
+                let dx = integer(params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        parsed.name.0.span(),
+                        "missing dx argument"
+                    )));
+                let dy = integer(params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        // ../tests/fail/analyze/mouse-move-missing-dy-argument.rs
+                        parsed.name.0.span(),
+                        "missing dy argument"
+                    )));
+                no_more_arguments(&mut params);
+                ControlCode::MouseMove(dx, dy)
+            },
+            "MouseButton" => {
+                let button = params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        parsed.name.0.span(),
+                        "missing button key code argument"
+                    ))
+                    .into();
+                no_more_arguments(&mut params);
+                ControlCode::MouseButton(button)
+            },
+            "MouseWheel" => {
+                let notches = integer(params
+                    .next()
+                    .unwrap_or_else(|| abort!(
+                        parsed.name.0.span(),
+                        "missing notches argument"
+                    )));
+                no_more_arguments(&mut params);
+                ControlCode::MouseWheel(notches)
+            },
             _ => {
                 abort!(
                     // ../tests/fail/analyze/unsupported-key-function.rs
@@ -139,7 +280,7 @@ impl From<&parse::LayerBody> for HashMap<KeyCode, Vec<ControlCode>> {
     }
 }
 
-const VALID_LAYER_OPTIONS: [&'static str; 1] = ["Active"];
+const VALID_LAYER_OPTIONS: [&'static str; 2] = ["Active", "Device"];
 
 impl From<&parse::Layer> for qsk_types::Layer {
     fn from(parsed: &parse::Layer) -> Self {
@@ -148,8 +289,22 @@ impl From<&parse::Layer> for qsk_types::Layer {
         match &parsed.opts {
             Some(layer_opts) => {
                 for opt in layer_opts.opts.iter() {
-                    match opt.to_string().as_str() {
-                        "Active" => layer.activate(),
+                    match (opt.to_string().as_str(), &opt.arg) {
+                        ("Active", None) => layer.activate(),
+                        ("Device", Some(index)) => match index.base10_parse() {
+                            Ok(device) => layer.scope_to_device(device),
+                            Err(e) => abort!(index.span(), "invalid device index: {}", e),
+                        },
+                        ("Device", None) => {
+                            // ../tests/fail/analyze/device-option-missing-index.rs
+                            abort!(
+                                opt.span(),
+                                "missing device index";
+                                help = "devices are numbered in the order they are passed to `qsk remap`, starting from 0");
+                        },
+                        ("Active", Some(arg)) => {
+                            abort!(arg.span(), "layer option takes no arguments")
+                        },
                         _ => {
                             // ../tests/fail/analyze/invalid-layer-option.rs
                             abort!(
@@ -187,6 +342,7 @@ pub fn validate_references(ast: &Ast) {
     let valid_layer_names: BTreeSet<String> = ast.iter()
         .map(|layer| layer.name.to_string())
         .collect();
+    let layer_count = ast.iter().count();
 
     // then iterate over all keymaps looking for all KeyFunctions that take a LayerRef
     for layer in ast.iter() {
@@ -194,10 +350,10 @@ pub fn validate_references(ast: &Ast) {
             match &keymaps.rhs {
                 parse::ControlCode::Function(kf) => {
                     match kf.name.to_string().as_str() {
-                        "TapToggle" | "TT" => {
+                        name if LAYER_REF_KEY_FUNCTIONS.contains(&name) => {
                             let layer_ref = &kf.params.0[0];
                             match layer_ref {
-                                parse::KeyFunctionParameter::StringParameter(sp) => {
+                                parse::KeyFunctionParameter::String(sp) => {
                                     if !valid_layer_names.contains(sp.to_string().as_str()) {
                                         abort!(
                                             layer_ref.span(),
@@ -206,6 +362,20 @@ pub fn validate_references(ast: &Ast) {
                                         )
                                     }
                                 },
+                                // rejected when converting to LayerRef
+                                parse::KeyFunctionParameter::Negative(_) => (),
+                                parse::KeyFunctionParameter::Index(ip) => {
+                                    if let LayerRef::ByIndex(i) = LayerRef::from(layer_ref.clone()) {
+                                        if i >= layer_count {
+                                            // ../tests/fail/analyze/layer-index-out-of-range.rs
+                                            abort!(
+                                                ip.span(),
+                                                "layer index out of range";
+                                                help = format!("{} layers are declared, indexed from 0", layer_count)
+                                            )
+                                        }
+                                    }
+                                },
                             }
                         },
                         _ => continue,
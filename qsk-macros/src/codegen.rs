@@ -3,6 +3,23 @@ use quote::quote;
 
 use crate::lower;
 
+impl From<&lower::LayerRef> for TokenStream {
+    fn from(lr: &lower::LayerRef) -> TokenStream {
+        match lr {
+            lower::LayerRef::ByName(path, name) => {
+                quote!(
+                    #path(#name.to_string())
+                )
+            },
+            lower::LayerRef::ByIndex(path, index) => {
+                quote!(
+                    #path(#index)
+                )
+            },
+        }
+    }
+}
+
 impl From<&lower::ControlCode> for TokenStream {
     fn from(cc: &lower::ControlCode) -> TokenStream {
         match cc {
@@ -13,14 +30,42 @@ impl From<&lower::ControlCode> for TokenStream {
             },
             lower::ControlCode::TapToggle(tt) => {
                 let tt_name = &tt.name;
-                let layer_ref_path = &tt.layer_ref.path;
-                let layer_ref_name = &tt.layer_ref.name;
+                let layer_ref = TokenStream::from(&tt.layer_ref);
                 let tap_key = &tt.tap_key;
                 quote!(
-                    vec![#tt_name(#layer_ref_path(#layer_ref_name.to_string()), #tap_key)]
+                    vec![#tt_name(#layer_ref, #tap_key)]
+                )
+            },
+            lower::ControlCode::LayerFunction(lf) => {
+                let lf_name = &lf.name;
+                let layer_ref = TokenStream::from(&lf.layer_ref);
+                quote!(
+                    vec![#lf_name(#layer_ref)]
+                )
+            },
+            lower::ControlCode::ModTap(mt) => {
+                let mt_name = &mt.name;
+                let modifier = &mt.modifier;
+                let tap_key = &mt.tap_key;
+                quote!(
+                    vec![#mt_name(#modifier, #tap_key)]
+                )
+            },
+            lower::ControlCode::MouseFunction(mf) => {
+                let mf_name = &mf.name;
+                let args = &mf.args;
+                quote!(
+                    vec![#mf_name(#(#args),*)]
                 )
             },
-            lower::ControlCode::Exit(path) => {
+            lower::ControlCode::MouseButton(mb) => {
+                let mb_name = &mb.name;
+                let button = &mb.button;
+                quote!(
+                    vec![#mb_name(#button)]
+                )
+            },
+            lower::ControlCode::TogglePassthrough(path) | lower::ControlCode::Exit(path) => {
                 quote!(
                     vec![#path]
                 )
@@ -53,7 +98,7 @@ impl From<&lower::Layer> for TokenStream {
             .map(TokenStream::from)
             .collect();
 
-        quote!(
+        let constructor = quote!(
             qsk_types::Layer::from_hashmap(
                 String::from(#name),
                 std::collections::HashMap::from([
@@ -61,7 +106,17 @@ impl From<&lower::Layer> for TokenStream {
                 ]),
                 #active,
             )
-        )
+        );
+        match &layer.device {
+            Some(device) => quote!(
+                {
+                    let mut layer = #constructor;
+                    layer.scope_to_device(#device);
+                    layer
+                }
+            ),
+            None => constructor,
+        }
     }
 }
 
@@ -77,3 +132,17 @@ pub fn codegen(ir: lower::Ir) -> TokenStream {
         )
     )
 }
+
+// Referencing included files through include_str! makes cargo rebuild the crate invoking remap!
+// whenever one of them changes.
+pub fn track_included_files(rust: TokenStream, files: Vec<String>) -> TokenStream {
+    if files.is_empty() {
+        return rust
+    }
+    quote!(
+        {
+            #(const _: &str = include_str!(#files);)*
+            #rust
+        }
+    )
+}
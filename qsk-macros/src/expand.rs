@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{Ident, LitStr, Token};
+
+use qsk_types::KeyCode;
+
+use crate::parse;
+use crate::parse::{Ast, Include, KeyMaps, LayerBody};
+
+struct Expander<'a> {
+    aliases: HashMap<String, parse::ControlCode>,
+    fragments: BTreeMap<String, &'a LayerBody>,
+    // fragments and files currently being expanded, used to detect include cycles
+    stack: Vec<String>,
+    files: Vec<String>,
+}
+
+impl<'a> Expander<'a> {
+    fn new(ast: &'a Ast) -> Self {
+        let mut aliases = HashMap::new();
+        for alias in ast.aliases.iter() {
+            let name = alias.name.to_string();
+            if KeyCode::from_str(parse::Key(alias.name.clone()).to_string().as_str()).is_ok() {
+                // ../tests/fail/analyze/alias-shadows-key-code.rs
+                abort!(alias.name.span(), "alias name conflicts with a key code")
+            }
+            if aliases.contains_key(&name) {
+                abort!(alias.name.span(), "alias is already defined")
+            }
+            // aliases may refer to aliases defined before them
+            let rhs = resolve_alias(&aliases, &alias.rhs);
+            aliases.insert(name, rhs);
+        }
+
+        let mut fragments = BTreeMap::new();
+        for fragment in ast.fragments.iter() {
+            if fragments.insert(fragment.name.to_string(), &fragment.body).is_some() {
+                abort!(fragment.name.span(), "fragment is already defined")
+            }
+        }
+
+        Expander {
+            aliases,
+            fragments,
+            stack: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    // Included maps come first so that maps declared in the body itself take precedence over them
+    // when the layer's HashMap is collected.
+    fn expand_body(&mut self, body: &LayerBody) -> Vec<KeyMaps> {
+        let mut maps = Vec::new();
+        for include in body.includes.iter() {
+            match include {
+                Include::Fragment(name) => maps.extend(self.expand_fragment(name)),
+                Include::File(path) => maps.extend(self.expand_file(path)),
+            }
+        }
+        maps.extend(body.iter().cloned());
+        maps
+    }
+
+    fn expand_fragment(&mut self, name: &Ident) -> Vec<KeyMaps> {
+        let key = name.to_string();
+        let body = match self.fragments.get(&key) {
+            Some(body) => *body,
+            // ../tests/fail/analyze/include-nonexistent-fragment.rs
+            None => abort!(
+                name.span(),
+                "fragment does not exist";
+                help = format!("existing fragments include: {:?}", self.fragments.keys().collect::<Vec<_>>())
+            ),
+        };
+        if self.stack.contains(&key) {
+            abort!(name.span(), "fragment includes itself")
+        }
+        self.stack.push(key);
+        let maps = self.expand_body(body);
+        self.stack.pop();
+        maps
+    }
+
+    // Paths are relative to the directory containing the Cargo.toml of the crate invoking remap!.
+    fn expand_file(&mut self, path: &LitStr) -> Vec<KeyMaps> {
+        let mut full_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+        full_path.push(path.value());
+        let source = match std::fs::read_to_string(&full_path) {
+            Ok(s) => s,
+            Err(e) => abort!(path.span(), format!("unable to read {}: {}", full_path.display(), e)),
+        };
+        let ts = match TokenStream::from_str(&source) {
+            Ok(ts) => ts,
+            Err(e) => abort!(path.span(), format!("unable to tokenize {}: {:?}", full_path.display(), e)),
+        };
+        let body = match (|stream: ParseStream| LayerBody::parse_items(stream)).parse2(ts) {
+            Ok(body) => body,
+            Err(e) => abort!(path.span(), format!("unable to parse {}: {}", full_path.display(), e)),
+        };
+        let key = full_path.canonicalize().unwrap_or(full_path).display().to_string();
+        if self.stack.contains(&key) {
+            // ../tests/fail/analyze/include-file-cycle.rs
+            abort!(path.span(), "file includes itself")
+        }
+        self.files.push(key.clone());
+        self.stack.push(key);
+        let maps = self.expand_body(&body);
+        self.stack.pop();
+        maps
+    }
+}
+
+fn resolve_alias(aliases: &HashMap<String, parse::ControlCode>, cc: &parse::ControlCode) -> parse::ControlCode {
+    match cc {
+        parse::ControlCode::Key(key) => match aliases.get(&key.0.to_string()) {
+            Some(rhs) => rhs.clone(),
+            None => cc.clone(),
+        },
+        _ => cc.clone(),
+    }
+}
+
+/// Substitutes aliases and fragment includes so that later stages only have to deal with layers of
+/// plain key maps. Also returns the paths of all included files.
+pub fn expand(ast: Ast) -> (Ast, Vec<String>) {
+    let mut expander = Expander::new(&ast);
+    let mut layers: Punctuated<parse::Layer, Token![,]> = Punctuated::new();
+    for layer in ast.layers.iter() {
+        let mut maps = Punctuated::new();
+        for km in expander.expand_body(&layer.body) {
+            maps.push(KeyMaps {
+                rhs: resolve_alias(&expander.aliases, &km.rhs),
+                lhs: km.lhs,
+            });
+        }
+        layers.push(parse::Layer {
+            name: layer.name.clone(),
+            opts: layer.opts.clone(),
+            body: LayerBody {
+                maps,
+                includes: Vec::new(),
+            },
+        });
+    }
+    let files = expander.files;
+    (
+        Ast {
+            aliases: Vec::new(),
+            fragments: Vec::new(),
+            layers,
+        },
+        files,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    #[test]
+    fn expand_aliases_and_fragments() {
+        let ast = parse::parse(quote!(
+            alias nav_f = TT(Navigation, F);
+            let nav = nav_f;
+            fragment Arrows: {
+                H -> LEFT,
+                L -> RIGHT,
+            },
+            fragment VimNav: {
+                include Arrows,
+                Y -> HOME,
+            },
+            ModLayer[Active]: {
+                F -> nav,
+            },
+            Navigation: {
+                L -> END,
+                include VimNav,
+            },
+        ));
+        let (expanded, files) = expand(ast);
+        assert_that!(&files.len(), eq(0));
+
+        let layers: Vec<&parse::Layer> = expanded.iter().collect();
+        assert_that!(&layers.len(), eq(2));
+
+        let f = layers[0].body.iter().next().unwrap();
+        assert_that!(&f.rhs, eq(syn::parse2::<parse::ControlCode>(quote!(TT(Navigation, F))).unwrap()));
+
+        let maps: Vec<(String, String)> = layers[1].body.iter()
+            .map(|km| (km.lhs.to_string(), match &km.rhs {
+                parse::ControlCode::Key(k) => k.to_string(),
+                _ => String::new(),
+            }))
+            .collect();
+        assert_that!(&maps, eq(vec![
+            ("KC_H".to_string(), "KC_LEFT".to_string()),
+            ("KC_L".to_string(), "KC_RIGHT".to_string()),
+            ("KC_Y".to_string(), "KC_HOME".to_string()),
+            ("KC_L".to_string(), "KC_END".to_string()),
+        ]));
+    }
+}
@@ -31,6 +31,7 @@ use proc_macro_error::proc_macro_error;
 
 mod analyze;
 mod codegen;
+mod expand;
 mod lower;
 mod parse;
 
@@ -38,8 +39,9 @@ mod parse;
 #[proc_macro_error]
 pub fn remap(ts: TokenStream) -> TokenStream {
     let ast = parse::parse(ts.clone().into());
+    let (ast, files) = expand::expand(ast);
     let model = analyze::analyze(ast);
     let ir = lower::lower(model);
-    let rust = codegen::codegen(ir).into();
+    let rust = codegen::track_included_files(codegen::codegen(ir), files).into();
     rust
 }
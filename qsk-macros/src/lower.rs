@@ -1,27 +1,72 @@
 use proc_macro2::Span;
 use proc_macro_error::abort_call_site;
-use syn::{LitBool, LitStr, Ident, Path, PathSegment, PathArguments};
+use syn::{LitBool, LitInt, LitStr, Ident, Path, PathSegment, PathArguments};
 use syn::punctuated::Punctuated;
 
 use qsk_types;
 
-pub struct LayerRefByName {
-    pub(crate) path: Path,
-    pub(crate) name: LitStr,
+pub enum LayerRef {
+    ByName(Path, LitStr),
+    ByIndex(Path, LitInt),
 }
 
 pub struct TapToggle {
     pub(crate) name: Path,
-    pub(crate) layer_ref: LayerRefByName,
+    pub(crate) layer_ref: LayerRef,
     pub(crate) tap_key: Path,
 }
 
+pub struct LayerFunction {
+    pub(crate) name: Path,
+    pub(crate) layer_ref: LayerRef,
+}
+
+pub struct ModTap {
+    pub(crate) name: Path,
+    pub(crate) modifier: Path,
+    pub(crate) tap_key: Path,
+}
+
+pub struct MouseFunction {
+    pub(crate) name: Path,
+    pub(crate) args: Vec<i32>,
+}
+
+pub struct MouseButton {
+    pub(crate) name: Path,
+    pub(crate) button: Path,
+}
+
 pub enum ControlCode {
     Key(Path),
     TapToggle(TapToggle),
+    LayerFunction(LayerFunction),
+    ModTap(ModTap),
+    MouseFunction(MouseFunction),
+    MouseButton(MouseButton),
+    TogglePassthrough(Path),
     Exit(Path),
 }
 
+impl From<&qsk_types::LayerRef> for LayerRef {
+    fn from(layer_ref: &qsk_types::LayerRef) -> LayerRef {
+        match layer_ref {
+            qsk_types::LayerRef::ByName(name) => {
+                LayerRef::ByName(
+                    path_from_vec_str(vec!["qsk_types", "LayerRef", "ByName"]),
+                    LitStr::new(name, Span::call_site()),
+                )
+            },
+            qsk_types::LayerRef::ByIndex(index) => {
+                LayerRef::ByIndex(
+                    path_from_vec_str(vec!["qsk_types", "LayerRef", "ByIndex"]),
+                    LitInt::new(&format!("{}usize", index), Span::call_site()),
+                )
+            },
+        }
+    }
+}
+
 impl From<&qsk_types::ControlCode> for ControlCode {
     fn from(cc: &qsk_types::ControlCode) -> ControlCode {
         match cc {
@@ -33,20 +78,58 @@ impl From<&qsk_types::ControlCode> for ControlCode {
             qsk_types::ControlCode::TapToggle(layer_ref, kc) => {
                 ControlCode::TapToggle(TapToggle{
                     name: control_code_path("TapToggle"),
-                    layer_ref: match layer_ref {
-                        qsk_types::LayerRef::ByName(name) => {
-                            LayerRefByName{
-                                path: path_from_vec_str(vec!["qsk_types", "LayerRef", "ByName"]),
-                                name: LitStr::new(name, Span::call_site()),
-                            }
-                        },
-                        qsk_types::LayerRef::ByIndex(_) => {
-                            abort_call_site!("referencing layers by index is unsupported")
-                        },
-                    },
+                    layer_ref: layer_ref.into(),
                     tap_key: keycode_path(&kc.to_string()),
                     })
             },
+            qsk_types::ControlCode::Momentary(layer_ref) => {
+                ControlCode::LayerFunction(LayerFunction{
+                    name: control_code_path("Momentary"),
+                    layer_ref: layer_ref.into(),
+                })
+            },
+            qsk_types::ControlCode::Toggle(layer_ref) => {
+                ControlCode::LayerFunction(LayerFunction{
+                    name: control_code_path("Toggle"),
+                    layer_ref: layer_ref.into(),
+                })
+            },
+            qsk_types::ControlCode::Switch(layer_ref) => {
+                ControlCode::LayerFunction(LayerFunction{
+                    name: control_code_path("Switch"),
+                    layer_ref: layer_ref.into(),
+                })
+            },
+            qsk_types::ControlCode::ModTap(modifier, kc) => {
+                ControlCode::ModTap(ModTap{
+                    name: control_code_path("ModTap"),
+                    modifier: keycode_path(&modifier.to_string()),
+                    tap_key: keycode_path(&kc.to_string()),
+                })
+            },
+            qsk_types::ControlCode::MouseMove(dx, dy) => {
+                ControlCode::MouseFunction(MouseFunction{
+                    name: control_code_path("MouseMove"),
+                    args: vec![*dx, *dy],
+                })
+            },
+            qsk_types::ControlCode::MouseWheel(notches) => {
+                ControlCode::MouseFunction(MouseFunction{
+                    name: control_code_path("MouseWheel"),
+                    args: vec![*notches],
+                })
+            },
+            qsk_types::ControlCode::MouseButton(button) => {
+                ControlCode::MouseButton(MouseButton{
+                    name: control_code_path("MouseButton"),
+                    button: keycode_path(&button.to_string()),
+                })
+            },
+            qsk_types::ControlCode::TogglePassthrough => {
+                ControlCode::TogglePassthrough(
+                    control_code_path("TogglePassthrough"),
+                )
+            },
             qsk_types::ControlCode::Exit => {
                 ControlCode::Exit(
                     control_code_path("Exit"),
@@ -67,6 +150,7 @@ pub struct KeyMap {
 pub struct Layer {
     pub(crate) name: LitStr,
     pub(crate) active: LitBool,
+    pub(crate) device: Option<LitInt>,
     pub(crate) maps: Vec<KeyMap>,
 }
 
@@ -75,10 +159,11 @@ impl From<&qsk_types::Layer> for Layer {
         Layer{
             name: LitStr::new(&layer.name, Span::call_site()),
             active: LitBool::new(layer.active, Span::call_site()),
+            device: layer.device.map(|d| LitInt::new(&d.to_string(), Span::call_site())),
             maps: layer
                 .iter()
                 .map(|(k, v)| KeyMap{
-                    key: event_code_to_path(k.into()),
+                    key: keycode_path(&k.to_string()),
                     control_code: v
                         .iter()
                         .map(|cc| {
@@ -110,17 +195,6 @@ pub fn lower(model: qsk_types::LayerComposer) -> Ir {
     model.into()
 }
 
-fn event_code_to_path(ec: &qsk_types::EventCode) -> Path {
-    match ec {
-        qsk_types::EventCode::KeyCode(kc) => {
-            keycode_path(&kc.to_string())
-        },
-        qsk_types::EventCode::SynCode(_) => {
-            abort_call_site!("syn code not allowed in proc macro")
-        },
-    }
-}
-
 fn keycode_path(keycode_str: &str) -> Path {
     path_from_vec_str(vec![
                       "qsk_types", "KeyCode", keycode_str,
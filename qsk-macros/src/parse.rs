@@ -1,5 +1,7 @@
+use std::fmt;
+
 use proc_macro2::{TokenStream, TokenTree, Span};
-use syn::{braced, bracketed, Result, Token, Ident, parse2};
+use syn::{braced, bracketed, parenthesized, token, Result, Token, Ident, LitInt, LitStr, parse2};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use proc_macro_error::abort;
@@ -20,27 +22,77 @@ impl StringParameter {
     }
 }
 
+#[repr(transparent)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexParameter(pub(crate) LitInt);
+
+impl fmt::Display for IndexParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl IndexParameter {
+    pub(crate) fn span(&self) -> Span {
+        self.0.span()
+    }
+}
+
+// A negative integer, eg '-5' in 'MouseMove(-5, 0)'; the literal holds its magnitude.
+#[repr(transparent)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeParameter(pub(crate) LitInt);
+
+impl fmt::Display for NegativeParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "-{}", self.0)
+    }
+}
+
+impl NegativeParameter {
+    pub(crate) fn span(&self) -> Span {
+        self.0.span()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum KeyFunctionParameter {
-    StringParameter(StringParameter),
+    String(StringParameter),
+    Index(IndexParameter),
+    Negative(NegativeParameter),
 }
 
 impl Parse for KeyFunctionParameter {
     fn parse(stream: ParseStream) -> Result<Self> {
-        Ok(KeyFunctionParameter::StringParameter(StringParameter(stream.parse()?)))
+        if stream.peek(LitInt) {
+            let lit: LitInt = stream.parse()?;
+            // token streams built outside of a macro invocation may carry the sign in the literal
+            if let Some(magnitude) = lit.base10_digits().strip_prefix('-') {
+                let magnitude = LitInt::new(&format!("{}{}", magnitude, lit.suffix()), lit.span());
+                return Ok(KeyFunctionParameter::Negative(NegativeParameter(magnitude)))
+            }
+            return Ok(KeyFunctionParameter::Index(IndexParameter(lit)))
+        }
+        if stream.peek(Token![-]) && stream.peek2(LitInt) {
+            stream.parse::<Token![-]>()?;
+            return Ok(KeyFunctionParameter::Negative(NegativeParameter(stream.parse()?)))
+        }
+        Ok(KeyFunctionParameter::String(StringParameter(stream.parse()?)))
     }
 }
 
 impl KeyFunctionParameter {
     pub(crate) fn span(&self) -> Span {
         match self {
-            Self::StringParameter(ident) => ident.span(),
+            Self::String(ident) => ident.span(),
+            Self::Index(index) => index.span(),
+            Self::Negative(negative) => negative.span(),
         }
     }
 }
 
 #[repr(transparent)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct KeyFunctionName(pub Ident);
 
 impl ToString for KeyFunctionName {
@@ -56,7 +108,7 @@ impl KeyFunctionName {
 }
 
 #[repr(transparent)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Key(pub Ident);
 
 impl ToString for Key {
@@ -84,7 +136,7 @@ impl Parse for KeyFunctionParameters {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct KeyFunction {
     pub(crate) name: KeyFunctionName,
     pub(crate) params: KeyFunctionParameters,
@@ -99,7 +151,7 @@ impl KeyFunction {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ControlCode {
     Key(Key),
     Function(KeyFunction),
@@ -128,10 +180,11 @@ impl Parse for ControlCode {
             // if there is a second token tree that's not a punct and it's a group
             if let Some((tt, next)) = rest.token_tree() {
                 match &tt {
-                    // match comma at end of straight KeyMaps, eg 'Y -> HOME,'
+                    // match comma at end of straight KeyMaps, eg 'Y -> HOME,', or semicolon at
+                    // the end of an alias, eg 'alias home = HOME;'
                     //                                                      ^
                     TokenTree::Punct(punct) => {
-                        if punct.as_char() != ',' {
+                        if punct.as_char() != ',' && punct.as_char() != ';' {
                             return Err(cursor.error("unexpected punctuation"))
                         }
                         return Ok((ControlCode::Key(Key(name)), rest))
@@ -168,7 +221,7 @@ impl Parse for ControlCode {
                     // match comma at end of straight KeyMaps, eg 'Y -> EXIT(),'
                     //                                                        ^
                     TokenTree::Punct(punct) => {
-                        if punct.as_char() != ',' {
+                        if punct.as_char() != ',' && punct.as_char() != ';' {
                             return Err(cursor.error("unexpected punctuation"))
                         }
                     },
@@ -182,7 +235,7 @@ impl Parse for ControlCode {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct KeyMaps {
     pub(crate) lhs: Key,
     pub(crate) rhs: ControlCode,
@@ -200,29 +253,102 @@ impl Parse for KeyMaps {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Include {
+    Fragment(Ident),
+    File(LitStr),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LayerBody {
     pub(crate) maps: Punctuated<KeyMaps, Token![,]>,
+    pub(crate) includes: Vec<Include>,
 }
 
 impl LayerBody {
     pub fn iter(&self) -> impl Iterator<Item = &KeyMaps> {
         self.maps.iter()
     }
+
+    // parses the comma-separated contents of a layer body without its enclosing braces; this is
+    // also the format of files pulled in with 'include "path"'
+    pub(crate) fn parse_items(stream: ParseStream) -> Result<Self> {
+        let mut maps = Punctuated::new();
+        let mut includes = Vec::new();
+        while !stream.is_empty() {
+            if peek_keyword(stream, "include") && (stream.peek2(Ident) || stream.peek2(LitStr)) {
+                stream.parse::<Ident>()?;
+                if stream.peek(LitStr) {
+                    includes.push(Include::File(stream.parse()?));
+                } else {
+                    includes.push(Include::Fragment(stream.parse()?));
+                }
+            } else {
+                maps.push_value(stream.parse()?);
+            }
+            if stream.is_empty() {
+                break;
+            }
+            let comma = stream.parse::<Token![,]>()?;
+            if !maps.empty_or_trailing() {
+                maps.push_punct(comma);
+            }
+        }
+        Ok(LayerBody{
+            maps,
+            includes,
+        })
+    }
 }
 
 impl Parse for LayerBody {
     fn parse(stream: ParseStream) -> Result<Self> {
         let content;
         braced!(content in stream);
-        Ok(LayerBody{
-            maps: content.parse_terminated(KeyMaps::parse)?,
-        })
+        LayerBody::parse_items(&content)
+    }
+}
+
+// returns true if the next token is the identifier `keyword`
+fn peek_keyword(stream: ParseStream, keyword: &str) -> bool {
+    stream.peek(Ident) && stream.fork().parse::<Ident>().is_ok_and(|ident| ident == keyword)
+}
+
+// A layer option, optionally taking an integer argument, eg 'Active' or 'Device(1)'.
+#[derive(Clone)]
+pub struct LayerOpt {
+    pub(crate) name: Ident,
+    pub(crate) arg: Option<LitInt>,
+}
+
+impl Parse for LayerOpt {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let name = stream.parse()?;
+        let mut arg = None;
+        if stream.peek(token::Paren) {
+            let content;
+            parenthesized!(content in stream);
+            arg = Some(content.parse()?);
+        }
+        Ok(LayerOpt { name, arg })
+    }
+}
+
+impl fmt::Display for LayerOpt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
     }
 }
 
+impl LayerOpt {
+    pub(crate) fn span(&self) -> Span {
+        self.name.span()
+    }
+}
+
+#[derive(Clone)]
 pub struct LayerOpts {
-    pub(crate) opts: Punctuated<Ident, Token![,]>,
+    pub(crate) opts: Punctuated<LayerOpt, Token![,]>,
 }
 
 impl Parse for LayerOpts {
@@ -230,11 +356,12 @@ impl Parse for LayerOpts {
         let content;
         bracketed!(content in stream);
         Ok(LayerOpts{
-            opts: content.parse_terminated(Ident::parse)?,
+            opts: content.parse_terminated(LayerOpt::parse)?,
         })
     }
 }
 
+#[derive(Clone)]
 pub struct Layer {
     pub(crate) name: Ident,
     pub(crate) opts: Option<LayerOpts>,
@@ -257,7 +384,52 @@ impl Parse for Layer {
     }
 }
 
+/// A named control code, eg 'alias nav_f = TT(Navigation, F);', that can be used on the right-hand
+/// side of any key map.
+pub struct Alias {
+    pub(crate) name: Ident,
+    pub(crate) rhs: ControlCode,
+}
+
+impl Parse for Alias {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        if stream.peek(Token![let]) {
+            stream.parse::<Token![let]>()?;
+        } else {
+            stream.parse::<Ident>()?; // 'alias'
+        }
+        let name = stream.parse()?;
+        stream.parse::<Token![=]>()?;
+        let rhs = stream.parse()?;
+        stream.parse::<Token![;]>()?;
+        Ok(Alias {
+            name,
+            rhs,
+        })
+    }
+}
+
+/// A named set of key maps, eg 'fragment VimNav: { H -> LEFT, }', that layers can include.
+pub struct Fragment {
+    pub(crate) name: Ident,
+    pub(crate) body: LayerBody,
+}
+
+impl Parse for Fragment {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        stream.parse::<Ident>()?; // 'fragment'
+        let name = stream.parse()?;
+        stream.parse::<Token![:]>()?;
+        Ok(Fragment {
+            name,
+            body: stream.parse()?,
+        })
+    }
+}
+
 pub struct Ast {
+    pub(crate) aliases: Vec<Alias>,
+    pub(crate) fragments: Vec<Fragment>,
     pub(crate) layers: Punctuated<Layer, Token![,]>,
 }
 
@@ -269,8 +441,29 @@ impl Ast {
 
 impl Parse for Ast {
     fn parse(stream: ParseStream) -> Result<Self> {
-        let layers = stream.parse_terminated(Layer::parse)?;
+        let mut aliases = Vec::new();
+        let mut fragments = Vec::new();
+        let mut layers = Punctuated::new();
+        while !stream.is_empty() {
+            // the keywords are only treated as such when followed by another identifier so that
+            // they can still be used as layer names
+            if stream.peek(Token![let]) || (peek_keyword(stream, "alias") && stream.peek2(Ident)) {
+                aliases.push(stream.parse()?);
+                continue;
+            }
+            if peek_keyword(stream, "fragment") && stream.peek2(Ident) {
+                fragments.push(stream.parse()?);
+            } else {
+                layers.push(stream.parse()?);
+            }
+            if stream.is_empty() {
+                break;
+            }
+            stream.parse::<Token![,]>()?;
+        }
         Ok(Ast {
+            aliases,
+            fragments,
             layers,
         })
     }
@@ -350,7 +543,7 @@ mod tests {
         for param in params {
             expected_params
                 .push(
-                    KeyFunctionParameter::StringParameter(StringParameter(
+                    KeyFunctionParameter::String(StringParameter(
                         Ident::new(param, Span::call_site())
                     ))
                 );
@@ -378,6 +571,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_control_code_function_layer_index() -> Result<()> {
+        let ts = quote!(TT(1, F));
+        let parsed = parse2::<ControlCode>(ts)?;
+
+        let mut expected_params: Punctuated<KeyFunctionParameter, Comma> = Punctuated::new();
+        expected_params.push(KeyFunctionParameter::Index(IndexParameter(
+            LitInt::new("1", Span::call_site())
+        )));
+        expected_params.push(KeyFunctionParameter::String(StringParameter(
+            Ident::new("F", Span::call_site())
+        )));
+        let expected = ControlCode::Function(
+            KeyFunction{
+                name: KeyFunctionName(Ident::new("TT", Span::call_site())),
+                params: KeyFunctionParameters(expected_params),
+            }
+        );
+        assert_that!(&parsed, eq(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_control_code_function_negative_integer() -> Result<()> {
+        let ts = quote!(MouseMove(-5, 0));
+        let parsed = parse2::<ControlCode>(ts)?;
+
+        let mut expected_params: Punctuated<KeyFunctionParameter, Comma> = Punctuated::new();
+        expected_params.push(KeyFunctionParameter::Negative(NegativeParameter(
+            LitInt::new("5", Span::call_site())
+        )));
+        expected_params.push(KeyFunctionParameter::Index(IndexParameter(
+            LitInt::new("0", Span::call_site())
+        )));
+        let expected = ControlCode::Function(
+            KeyFunction{
+                name: KeyFunctionName(Ident::new("MouseMove", Span::call_site())),
+                params: KeyFunctionParameters(expected_params),
+            }
+        );
+        assert_that!(&parsed, eq(expected));
+        Ok(())
+    }
+
     #[test]
     fn parse_keymap() -> Result<()> {
         let ts = quote!(F -> TapToggle(Navigation, F));
@@ -414,7 +651,33 @@ mod tests {
         let km = KeyMaps{ lhs, rhs, };
         maps.push(km);
 
-        let expected = LayerBody{ maps, };
+        let expected = LayerBody{ maps, includes: Vec::new(), };
+
+        let parsed = parse2::<LayerBody>(ts)?;
+        assert_that!(&parsed, eq(expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_layer_body_includes() -> Result<()> {
+        let ts = quote!({
+            include VimNav,
+            Y -> HOME,
+            include "shared/nav.qsk",
+        });
+
+        let mut maps: Punctuated<KeyMaps, Comma> = Punctuated::new();
+        let rhs = ControlCode::Key(Key(Ident::new("HOME", Span::call_site())));
+        let lhs = Key(Ident::new("Y", Span::call_site()));
+        maps.push(KeyMaps{ lhs, rhs, });
+        maps.push_punct(Comma::default());
+
+        let includes = vec![
+            Include::Fragment(Ident::new("VimNav", Span::call_site())),
+            Include::File(LitStr::new("shared/nav.qsk", Span::call_site())),
+        ];
+        let expected = LayerBody{ maps, includes, };
 
         let parsed = parse2::<LayerBody>(ts)?;
         assert_that!(&parsed, eq(expected));
@@ -0,0 +1,13 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        alias HOME = TT(Navigation, F);
+        ModLayer[Active]: {
+            F -> HOME,
+        },
+        Navigation: {
+            END -> Exit(),
+        },
+    );
+}
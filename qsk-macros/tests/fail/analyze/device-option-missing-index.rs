@@ -0,0 +1,9 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        Numpad[Active, Device]: {
+            KP1 -> F1,
+        },
+    );
+}
@@ -0,0 +1,13 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        ModLayer[Active]: {
+            F -> TT(Navigation, F),
+        },
+        Navigation: {
+            include "../../../tests/fail/analyze/include-file-cycle.qsk",
+            END -> Exit(),
+        },
+    );
+}
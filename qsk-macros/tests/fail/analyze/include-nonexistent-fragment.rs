@@ -0,0 +1,16 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        fragment VimNav: {
+            H -> LEFT,
+        },
+        ModLayer[Active]: {
+            F -> TT(Navigation, F),
+        },
+        Navigation: {
+            include Arrows,
+            END -> Exit(),
+        },
+    );
+}
@@ -0,0 +1,12 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        ModLayer[Active]: {
+            F -> TT(2, F),
+        },
+        Navigation: {
+            END -> Exit(),
+        },
+    );
+}
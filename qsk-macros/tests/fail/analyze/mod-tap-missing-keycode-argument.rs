@@ -0,0 +1,9 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        ModLayer: {
+            A -> ModTap(LEFTCTRL),
+        },
+    );
+}
@@ -0,0 +1,12 @@
+use qsk_macros::remap;
+
+fn main() {
+    remap!(
+        ModLayer[Active]: {
+            F -> TT(Navigation, F),
+        },
+        Navigation: {
+            K -> MouseMove(-5),
+        },
+    );
+}
@@ -0,0 +1,31 @@
+use qsk_macros;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    qsk_macros::remap!(
+        alias nav_f = TT(Navigation, F);
+        let ctrl_a = MT(LEFTCTRL, A);
+        fragment VimNav: {
+            H -> LEFT,
+            J -> DOWN,
+            K -> UP,
+            L -> RIGHT,
+        },
+        fragment ExitOnEnd: {
+            END -> Exit(),
+        },
+        ModLayer[Active]: {
+            F -> nav_f,
+            A -> ctrl_a,
+        },
+        Navigation: {
+            include VimNav,
+            include ExitOnEnd,
+            Y -> HOME,
+        },
+        Work: {
+            include VimNav,
+            L -> nav_f,
+        },
+    )?;
+    Ok(())
+}
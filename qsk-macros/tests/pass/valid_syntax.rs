@@ -5,9 +5,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         ModLayer[Active]: {
             Y -> HOME,
             F -> TT(Navigation, F),
+            A -> MT(LEFTCTRL, A),
+            S -> ModTap(LEFTSHIFT, S),
+            CAPSLOCK -> MO(Navigation),
+            RIGHTALT -> Momentary(Navigation),
+            SCROLLLOCK -> TG(Navigation),
+            NUMLOCK -> Toggle(Navigation),
+            F12 -> TO(Navigation),
+            G -> TT(1, G),
+            F11 -> MO(1),
+            BTN_SIDE -> MO(Navigation),
+            BTN_EXTRA -> LEFTMETA,
+            WHEEL_LEFT -> VOLUMEDOWN,
+            WHEEL_RIGHT -> VOLUMEUP,
+            BTN_SOUTH -> ENTER,
+            ABS_HAT0Y_MINUS -> UP,
+            ABS_X_PLUS -> TT(Navigation, RIGHT),
         },
         Navigation: {
             END -> Exit(),
+            PAUSE -> TogglePassthrough(),
+            ESC -> Switch(ModLayer),
             Y -> HOME,
             U -> PAGEDOWN,
             I -> PAGEUP,
@@ -16,6 +34,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             J -> DOWN,
             K -> UP,
             SEMICOLON -> RIGHT,
+            W -> MouseMove(0, -5),
+            A -> MouseMove(-5, 0),
+            S -> MouseMove(0, 5),
+            D -> MouseMove(5, 0),
+            E -> MouseWheel(1),
+            Q -> MouseWheel(-1),
+            SPACE -> MouseButton(BTN_LEFT),
+        },
+        Numpad[Active, Device(1)]: {
+            KP1 -> F1,
         },
         TestGT32KeyMaps: {
             A -> B,
@@ -0,0 +1,94 @@
+//! Measures how long `LayerComposer` takes to transform a single input event, which is latency
+//! added to every keypress. Run with `cargo bench -p qsk-types`.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::events::{EventCode, InputEvent, KeyCode, KeyCode::*, KeyState};
+use qsk_types::layer_composer::{InputTransformer, LayerComposer};
+use qsk_types::layers::Layer;
+
+fn layer(name: &str, maps: Vec<(KeyCode, ControlCode)>, active: bool) -> Layer {
+    let map: HashMap<KeyCode, Vec<ControlCode>> = maps.into_iter().map(|(kc, cc)| (kc, vec![cc])).collect();
+    Layer::from_hashmap(name.to_string(), map, active)
+}
+
+// A base layer with a remapped key, a mod-tap and a layer key, several inactive layers that are
+// skipped on every event, and a navigation layer on top.
+fn composer() -> LayerComposer {
+    let mut layers = vec![layer(
+        "base",
+        vec![
+            (KC_CAPSLOCK, ControlCode::KeyMap(KC_ESC)),
+            (KC_A, ControlCode::ModTap(KC_LEFTCTRL, KC_A)),
+            (KC_SPACE, ControlCode::Momentary(LayerRef::ByName("navigation".to_string()))),
+        ],
+        true,
+    )];
+    for i in 0..8 {
+        layers.push(layer(&format!("unused{}", i), vec![(KC_Q, ControlCode::KeyMap(KC_W))], false));
+    }
+    layers.push(layer(
+        "navigation",
+        vec![
+            (KC_H, ControlCode::KeyMap(KC_LEFT)),
+            (KC_J, ControlCode::KeyMap(KC_DOWN)),
+            (KC_K, ControlCode::KeyMap(KC_UP)),
+            (KC_L, ControlCode::KeyMap(KC_RIGHT)),
+        ],
+        false,
+    ));
+    LayerComposer::from_layers(layers).unwrap()
+}
+
+fn key(kc: KeyCode, state: KeyState) -> InputEvent {
+    InputEvent {
+        time: SystemTime::UNIX_EPOCH,
+        code: EventCode::KeyCode(kc),
+        state,
+        value: state as i32,
+        device: 0,
+    }
+}
+
+fn transform(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transform");
+    let mut composer = composer();
+    let mut output = Vec::with_capacity(16);
+
+    group.bench_function("passthrough", |b| {
+        b.iter(|| {
+            output.clear();
+            composer.transform(black_box(key(KC_E, KeyState::Down)), &mut output);
+        })
+    });
+    group.bench_function("remap", |b| {
+        b.iter(|| {
+            output.clear();
+            composer.transform(black_box(key(KC_CAPSLOCK, KeyState::Down)), &mut output);
+        })
+    });
+    group.bench_function("mod_tap_tap", |b| {
+        b.iter(|| {
+            output.clear();
+            composer.transform(black_box(key(KC_A, KeyState::Down)), &mut output);
+            composer.transform(black_box(key(KC_A, KeyState::Up)), &mut output);
+        })
+    });
+    group.bench_function("momentary_layer", |b| {
+        b.iter(|| {
+            output.clear();
+            composer.transform(black_box(key(KC_SPACE, KeyState::Down)), &mut output);
+            composer.transform(black_box(key(KC_J, KeyState::Down)), &mut output);
+            composer.transform(black_box(key(KC_J, KeyState::Up)), &mut output);
+            composer.transform(black_box(key(KC_SPACE, KeyState::Up)), &mut output);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, transform);
+criterion_main!(benches);
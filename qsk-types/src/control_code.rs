@@ -1,5 +1,7 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
-use crate::events::{InputEvent, KeyCode};
+use crate::events::{EventCode, InputEvent, KeyCode};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum LayerRef {
@@ -7,11 +9,48 @@ pub enum LayerRef {
     ByName(String),
 }
 
+impl fmt::Display for LayerRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerRef::ByIndex(index) => write!(f, "{}", index),
+            LayerRef::ByName(name) => write!(f, "{:?}", name),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum ControlCode {
     InputEvent(InputEvent),
     KeyMap(KeyCode),
     TapToggle(LayerRef, KeyCode),
+    Momentary(LayerRef),
+    Toggle(LayerRef),
+    Switch(LayerRef),
+    ModTap(KeyCode, KeyCode),
+    /// Moves the pointer by (dx, dy) per step while held, accelerating according to the
+    /// composer's `MouseKeysConfig`.
+    MouseMove(i32, i32),
+    /// Presses a mouse button, eg `KC_BTN_LEFT`, while held.
+    MouseButton(KeyCode),
+    /// Scrolls the wheel by the given number of notches per step while held; positive scrolls up.
+    MouseWheel(i32),
+    /// Switches between remapping and passing every key through unchanged. While keys pass
+    /// through, the key this is mapped to still switches back.
+    TogglePassthrough,
     Exit,
 }
 
+
+impl ControlCode {
+    /// Returns the keys this control code may press on the output device.
+    pub fn output_keys(&self) -> Vec<KeyCode> {
+        match self {
+            ControlCode::InputEvent(InputEvent { code: EventCode::KeyCode(kc), .. }) => vec![*kc],
+            ControlCode::KeyMap(kc) | ControlCode::TapToggle(_, kc) | ControlCode::MouseButton(kc) => {
+                vec![*kc]
+            }
+            ControlCode::ModTap(modifier, kc) => vec![*modifier, *kc],
+            _ => Vec::new(),
+        }
+    }
+}
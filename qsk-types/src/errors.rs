@@ -1,9 +1,17 @@
 use thiserror;
 
+use crate::control_code::LayerRef;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("layer validation error")]
     LayerValidationError,
+
+    #[error("at most {} layers are supported, got {0}", crate::layers::MAX_LAYERS)]
+    TooManyLayers(usize),
+
+    #[error("no layer {0}")]
+    NoSuchLayer(LayerRef),
 }
@@ -1,20 +1,41 @@
 use std::time::SystemTime;
 
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use strum_macros::{EnumString, Display};
 
 /// InputEvent is a qsk-specific struct modeled in large part after evdev_rs::InputEvent.
-/// Although evdev_rs::InputEvent actually supports a large range of Linux-specific input events,
-/// we focus here on keyboard and synchronization events specifically since keyboard events are the
-/// primary concern of qsk initially and synchronization needs to be represented. Abstracting away
-/// from Linux-specific event handling in this way will enable us to support input event systems
-/// for other OSes in the future.
+/// Keyboard and synchronization events are modeled in detail since keyboard events are the primary
+/// concern of qsk and synchronization needs to be represented; other event types are carried along
+/// so that they can be passed through unchanged. Abstracting away from Linux-specific event
+/// handling in this way will enable us to support input event systems for other OSes in the
+/// future.
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct InputEvent {
     pub time: SystemTime,
     pub code: EventCode,
     pub state: KeyState,
+    /// The raw value of non-key events, eg the distance moved along a relative axis. Key events
+    /// are described by `state` instead.
+    #[serde(default)]
+    pub value: i32,
+    /// Identifies the input device the event was read from, so that layers can be scoped to one
+    /// of several devices. Events synthesized by qsk itself come from device 0.
+    #[serde(default)]
+    pub device: DeviceId,
+}
+
+pub type DeviceId = u16;
+
+impl InputEvent {
+    /// Returns the value that should be written to an output device for this event.
+    pub fn raw_value(&self) -> i32 {
+        match self.code {
+            EventCode::KeyCode(_) => self.state as i32,
+            _ => self.value,
+        }
+    }
 }
 
 #[derive(
@@ -32,6 +53,50 @@ pub enum KeyState {
 pub enum EventCode {
     KeyCode(KeyCode),
     SynCode(SynCode),
+    Misc(MiscCode),
+    Relative(RelativeCode),
+    Absolute(AbsoluteCode),
+    Led(LedCode),
+    /// Any other event as its raw (type, code) pair, including key codes missing from `KeyCode`.
+    Raw(u16, u16),
+}
+
+/// Linux input event types, see linux/input-event-codes.h.
+pub const EV_SYN: u16 = 0x00;
+pub const EV_KEY: u16 = 0x01;
+pub const EV_REL: u16 = 0x02;
+pub const EV_ABS: u16 = 0x03;
+pub const EV_MSC: u16 = 0x04;
+pub const EV_LED: u16 = 0x11;
+
+impl EventCode {
+    /// Builds an `EventCode` from a Linux event type and code, falling back to `EventCode::Raw`
+    /// for anything that isn't modeled.
+    pub fn from_raw(ty: u16, code: u16) -> EventCode {
+        let ec = match ty {
+            EV_SYN => SynCode::from_u16(code).map(EventCode::SynCode),
+            EV_KEY => KeyCode::from_u16(code).map(EventCode::KeyCode),
+            EV_REL => RelativeCode::from_u16(code).map(EventCode::Relative),
+            EV_ABS => AbsoluteCode::from_u16(code).map(EventCode::Absolute),
+            EV_MSC => MiscCode::from_u16(code).map(EventCode::Misc),
+            EV_LED => LedCode::from_u16(code).map(EventCode::Led),
+            _ => None,
+        };
+        ec.unwrap_or(EventCode::Raw(ty, code))
+    }
+
+    /// Returns the Linux event type and code of this `EventCode`.
+    pub fn to_raw(&self) -> (u16, u16) {
+        match self {
+            EventCode::SynCode(c) => (EV_SYN, c.to_u16().unwrap()),
+            EventCode::KeyCode(c) => (EV_KEY, c.to_u16().unwrap()),
+            EventCode::Relative(c) => (EV_REL, c.to_u16().unwrap()),
+            EventCode::Absolute(c) => (EV_ABS, c.to_u16().unwrap()),
+            EventCode::Misc(c) => (EV_MSC, c.to_u16().unwrap()),
+            EventCode::Led(c) => (EV_LED, c.to_u16().unwrap()),
+            EventCode::Raw(ty, code) => (*ty, *code),
+        }
+    }
 }
 
 /// Copied and pasted from evdev-rs 0.3.1 with s/KEY_/KC_/ to align more closely with QMK naming
@@ -285,6 +350,70 @@ pub enum KeyCode {
     KC_WWAN = 246,
     KC_RFKILL = 247,
     KC_MICMUTE = 248,
+    KC_BTN_0 = 256,
+    KC_BTN_1 = 257,
+    KC_BTN_2 = 258,
+    KC_BTN_3 = 259,
+    KC_BTN_4 = 260,
+    KC_BTN_5 = 261,
+    KC_BTN_6 = 262,
+    KC_BTN_7 = 263,
+    KC_BTN_8 = 264,
+    KC_BTN_9 = 265,
+    KC_BTN_LEFT = 272,
+    KC_BTN_RIGHT = 273,
+    KC_BTN_MIDDLE = 274,
+    KC_BTN_SIDE = 275,
+    KC_BTN_EXTRA = 276,
+    KC_BTN_FORWARD = 277,
+    KC_BTN_BACK = 278,
+    KC_BTN_TASK = 279,
+    KC_BTN_TRIGGER = 288,
+    KC_BTN_THUMB = 289,
+    KC_BTN_THUMB2 = 290,
+    KC_BTN_TOP = 291,
+    KC_BTN_TOP2 = 292,
+    KC_BTN_PINKIE = 293,
+    KC_BTN_BASE = 294,
+    KC_BTN_BASE2 = 295,
+    KC_BTN_BASE3 = 296,
+    KC_BTN_BASE4 = 297,
+    KC_BTN_BASE5 = 298,
+    KC_BTN_BASE6 = 299,
+    KC_BTN_DEAD = 303,
+    KC_BTN_SOUTH = 304,
+    KC_BTN_EAST = 305,
+    KC_BTN_C = 306,
+    KC_BTN_NORTH = 307,
+    KC_BTN_WEST = 308,
+    KC_BTN_Z = 309,
+    KC_BTN_TL = 310,
+    KC_BTN_TR = 311,
+    KC_BTN_TL2 = 312,
+    KC_BTN_TR2 = 313,
+    KC_BTN_SELECT = 314,
+    KC_BTN_START = 315,
+    KC_BTN_MODE = 316,
+    KC_BTN_THUMBL = 317,
+    KC_BTN_THUMBR = 318,
+    KC_BTN_TOOL_PEN = 320,
+    KC_BTN_TOOL_RUBBER = 321,
+    KC_BTN_TOOL_BRUSH = 322,
+    KC_BTN_TOOL_PENCIL = 323,
+    KC_BTN_TOOL_AIRBRUSH = 324,
+    KC_BTN_TOOL_FINGER = 325,
+    KC_BTN_TOOL_MOUSE = 326,
+    KC_BTN_TOOL_LENS = 327,
+    KC_BTN_TOOL_QUINTTAP = 328,
+    KC_BTN_STYLUS3 = 329,
+    KC_BTN_TOUCH = 330,
+    KC_BTN_STYLUS = 331,
+    KC_BTN_STYLUS2 = 332,
+    KC_BTN_TOOL_DOUBLETAP = 333,
+    KC_BTN_TOOL_TRIPLETAP = 334,
+    KC_BTN_TOOL_QUADTAP = 335,
+    KC_BTN_GEAR_DOWN = 336,
+    KC_BTN_GEAR_UP = 337,
     KC_OK = 352,
     KC_SELECT = 353,
     KC_GOTO = 354,
@@ -442,6 +571,10 @@ pub enum KeyCode {
     KC_ATTENDANT_OFF = 540,
     KC_ATTENDANT_TOGGLE = 541,
     KC_LIGHTS_TOGGLE = 542,
+    KC_BTN_DPAD_UP = 544,
+    KC_BTN_DPAD_DOWN = 545,
+    KC_BTN_DPAD_LEFT = 546,
+    KC_BTN_DPAD_RIGHT = 547,
     KC_ALS_TOGGLE = 560,
     KC_ROTATE_LOCK_TOGGLE = 561,
     KC_BUTTONCONFIG = 576,
@@ -481,6 +614,45 @@ pub enum KeyCode {
     KC_ONSCREEN_KEYBOARD = 632,
     KC_MAX = 767,
     NotImplemented = 768,
+    // Scroll wheel directions aren't key codes as far as Linux is concerned. These pseudo key
+    // codes lie beyond KC_MAX so no device can send them, but layers can map them to remap wheel
+    // movement; see `LayerComposer`.
+    KC_WHEEL_UP = 769,
+    KC_WHEEL_DOWN = 770,
+    KC_WHEEL_LEFT = 771,
+    KC_WHEEL_RIGHT = 772,
+    // Game controller axes are pushed past a threshold rather than pressed. The gamepad input
+    // source reports each axis direction as one of these pseudo key codes; see
+    // `qsk::device::gamepad`.
+    KC_ABS_X_MINUS = 773,
+    KC_ABS_X_PLUS = 774,
+    KC_ABS_Y_MINUS = 775,
+    KC_ABS_Y_PLUS = 776,
+    KC_ABS_Z_MINUS = 777,
+    KC_ABS_Z_PLUS = 778,
+    KC_ABS_RX_MINUS = 779,
+    KC_ABS_RX_PLUS = 780,
+    KC_ABS_RY_MINUS = 781,
+    KC_ABS_RY_PLUS = 782,
+    KC_ABS_RZ_MINUS = 783,
+    KC_ABS_RZ_PLUS = 784,
+    KC_ABS_THROTTLE_MINUS = 785,
+    KC_ABS_THROTTLE_PLUS = 786,
+    KC_ABS_RUDDER_MINUS = 787,
+    KC_ABS_RUDDER_PLUS = 788,
+    KC_ABS_GAS_MINUS = 789,
+    KC_ABS_GAS_PLUS = 790,
+    KC_ABS_BRAKE_MINUS = 791,
+    KC_ABS_BRAKE_PLUS = 792,
+    KC_ABS_HAT0X_MINUS = 793,
+    KC_ABS_HAT0X_PLUS = 794,
+    KC_ABS_HAT0Y_MINUS = 795,
+    KC_ABS_HAT0Y_PLUS = 796,
+}
+
+impl KeyCode {
+    /// One more than the highest key code, pseudo keys included, for tables indexed by key code.
+    pub const COUNT: usize = KeyCode::KC_ABS_HAT0Y_PLUS as usize + 1;
 }
 
 #[derive(
@@ -493,3 +665,132 @@ pub enum SynCode {
     Dropped = 3,
     Max = 15,
 }
+
+#[derive(
+    Serialize, Deserialize, FromPrimitive, ToPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum MiscCode {
+    Serial = 0,
+    PulseLed = 1,
+    Gesture = 2,
+    Raw = 3,
+    Scan = 4,
+    Timestamp = 5,
+    Max = 7,
+}
+
+#[derive(
+    Serialize, Deserialize, FromPrimitive, ToPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum RelativeCode {
+    X = 0,
+    Y = 1,
+    Z = 2,
+    RX = 3,
+    RY = 4,
+    RZ = 5,
+    HWheel = 6,
+    Dial = 7,
+    Wheel = 8,
+    Misc = 9,
+    Reserved = 10,
+    WheelHiRes = 11,
+    HWheelHiRes = 12,
+    Max = 15,
+}
+
+#[derive(
+    Serialize, Deserialize, FromPrimitive, ToPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum AbsoluteCode {
+    X = 0,
+    Y = 1,
+    Z = 2,
+    RX = 3,
+    RY = 4,
+    RZ = 5,
+    Throttle = 6,
+    Rudder = 7,
+    Wheel = 8,
+    Gas = 9,
+    Brake = 10,
+    Hat0X = 16,
+    Hat0Y = 17,
+    Hat1X = 18,
+    Hat1Y = 19,
+    Hat2X = 20,
+    Hat2Y = 21,
+    Hat3X = 22,
+    Hat3Y = 23,
+    Pressure = 24,
+    Distance = 25,
+    TiltX = 26,
+    TiltY = 27,
+    ToolWidth = 28,
+    Volume = 32,
+    Profile = 33,
+    Misc = 40,
+    Reserved = 46,
+    MTSlot = 47,
+    MTTouchMajor = 48,
+    MTTouchMinor = 49,
+    MTWidthMajor = 50,
+    MTWidthMinor = 51,
+    MTOrientation = 52,
+    MTPositionX = 53,
+    MTPositionY = 54,
+    MTToolType = 55,
+    MTBlobId = 56,
+    MTTrackingId = 57,
+    MTPressure = 58,
+    MTDistance = 59,
+    MTToolX = 60,
+    MTToolY = 61,
+    Max = 63,
+}
+
+#[derive(
+    Serialize, Deserialize, FromPrimitive, ToPrimitive, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum LedCode {
+    NumLock = 0,
+    CapsLock = 1,
+    ScrollLock = 2,
+    Compose = 3,
+    Kana = 4,
+    Sleep = 5,
+    Suspend = 6,
+    Mute = 7,
+    Misc = 8,
+    Mail = 9,
+    Charging = 10,
+    Max = 15,
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    #[test]
+    fn event_code_raw_round_trip() {
+        let cases = vec![
+            ((EV_SYN, 0), EventCode::SynCode(SynCode::Report)),
+            ((EV_KEY, 30), EventCode::KeyCode(KeyCode::KC_A)),
+            ((EV_REL, 8), EventCode::Relative(RelativeCode::Wheel)),
+            ((EV_ABS, 17), EventCode::Absolute(AbsoluteCode::Hat0Y)),
+            ((EV_MSC, 4), EventCode::Misc(MiscCode::Scan)),
+            ((EV_LED, 1), EventCode::Led(LedCode::CapsLock)),
+            // a key code that KeyCode doesn't know about
+            ((EV_KEY, 600), EventCode::Raw(EV_KEY, 600)),
+            // EV_SW
+            ((0x05, 0), EventCode::Raw(0x05, 0)),
+        ];
+        for ((ty, code), expected) in cases {
+            assert_that!(&EventCode::from_raw(ty, code), eq(expected));
+            assert_that!(&expected.to_raw(), eq((ty, code)));
+        }
+    }
+}
@@ -1,18 +1,45 @@
 use std::collections::HashMap;
-use std::ops::{Index, IndexMut};
-use std::slice::IterMut;
+use std::ops::Index;
 
-use crate::control_code::ControlCode;
-use crate::events::{InputEvent, EventCode, KeyCode};
+use num::FromPrimitive;
+use serde::{Deserialize, Serialize};
 
+use crate::control_code::{ControlCode, LayerRef};
+use crate::events::{DeviceId, EventCode, KeyCode};
+
+/// KeyMap maps key codes to the control codes they produce. It is a dense table indexed by key
+/// code, so that looking up a key on every event neither hashes nor allocates.
 #[derive(Clone)]
-pub struct KeyMap(HashMap<EventCode, Vec<ControlCode>>);
+pub struct KeyMap(Vec<Option<Vec<ControlCode>>>);
+
+impl KeyMap {
+    fn get(&self, kc: KeyCode) -> Option<&Vec<ControlCode>> {
+        self.0.get(kc as usize).and_then(Option::as_ref)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (KeyCode, &Vec<ControlCode>)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ccs)| Some((KeyCode::from_usize(i)?, ccs.as_ref()?)))
+    }
+}
+
+impl From<HashMap<KeyCode, Vec<ControlCode>>> for KeyMap {
+    fn from(map: HashMap<KeyCode, Vec<ControlCode>>) -> Self {
+        let mut table = vec![None; KeyCode::COUNT];
+        for (kc, ccs) in map {
+            table[kc as usize] = Some(ccs);
+        }
+        KeyMap(table)
+    }
+}
 
-impl Index<EventCode> for KeyMap {
+impl Index<KeyCode> for KeyMap {
     type Output = Vec<ControlCode>;
 
-    fn index(&self, index: EventCode) -> &Self::Output {
-        &self.0[&index]
+    fn index(&self, index: KeyCode) -> &Self::Output {
+        self.get(index).expect("key is not mapped")
     }
 }
 
@@ -20,50 +47,85 @@ impl Index<EventCode> for KeyMap {
 pub struct Layer {
     pub name: String,
     map: KeyMap,
+    /// Whether the layer is active when remapping starts. `LayerComposer` keeps track of which
+    /// layers are active from then on.
     pub active: bool,
-}
-
-fn copy_control_codes_for_input_event(e: InputEvent, ccs: &Vec<ControlCode>) -> Vec<ControlCode> {
-    ccs.iter()
-        .map(|cc| {
-            match cc {
-                ControlCode::KeyMap(kc) => {
-                    let mut cloned = e.clone();
-                    cloned.code = EventCode::KeyCode(*kc);
-                    ControlCode::InputEvent(cloned)
-                }
-                _ => cc.clone(),
-            }
-        })
-        .collect()
+    /// Restricts the layer to events from the given input device; see `InputEvent::device`.
+    pub device: Option<DeviceId>,
 }
 
 impl Layer {
     pub fn from_hashmap(name: String, map: HashMap<KeyCode, Vec<ControlCode>>, active: bool) -> Layer {
         Layer {
             name,
-            map: KeyMap(map
-                .iter()
-                .map(|(k, v)| (EventCode::KeyCode(*k), v.clone()) )
-                .collect()),
+            map: map.into(),
             active,
+            device: None,
         }
     }
 
-    pub(crate) fn transform(&mut self, e: InputEvent) -> Option<Vec<ControlCode>> {
-        match (self.map.0.get(&e.code), self.active) {
-            (Some(ccs), true) => Some(copy_control_codes_for_input_event(e.clone(), ccs)),
-            (Some(_), false) => None,
-            (None, _) => None,
+    /// Returns the control codes the layer maps the given event code to, if it applies to events
+    /// from `device`.
+    pub(crate) fn get(&self, code: &EventCode, device: DeviceId) -> Option<&[ControlCode]> {
+        match code {
+            EventCode::KeyCode(kc) if self.applies_to(device) => self.map.get(*kc).map(Vec::as_slice),
+            _ => None,
         }
     }
 
+    pub(crate) fn maps(&self, code: &EventCode, device: DeviceId) -> bool {
+        self.get(code, device).is_some()
+    }
+
+    fn applies_to(&self, device: DeviceId) -> bool {
+        self.device.is_none_or(|d| d == device)
+    }
+
     pub fn activate(&mut self) {
         self.active = true
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&EventCode, &Vec<ControlCode>)> {
-        self.map.0.iter()
+    pub fn scope_to_device(&mut self, device: DeviceId) {
+        self.device = Some(device)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (KeyCode, &Vec<ControlCode>)> {
+        self.map.iter()
+    }
+}
+
+/// Describes a layer of a running keymap, eg to report it over the control socket.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LayerInfo {
+    pub name: String,
+    pub active: bool,
+    pub device: Option<DeviceId>,
+    /// The keys the layer maps, in key code order.
+    pub keys: Vec<(KeyCode, Vec<ControlCode>)>,
+}
+
+/// The most layers a `LayerComposer` can compose, the number of bits in a `LayerSet`.
+pub const MAX_LAYERS: usize = 64;
+
+/// A set of layers by index, which is how `LayerComposer` tracks the active layers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayerSet(u64);
+
+impl LayerSet {
+    pub fn contains(&self, index: usize) -> bool {
+        index < MAX_LAYERS && self.0 & (1 << index) != 0
+    }
+
+    pub fn set(&mut self, index: usize, active: bool) {
+        match active {
+            true => self.0 |= 1 << index,
+            false => self.0 &= !(1 << index),
+        }
+    }
+
+    /// Removes every layer except the bottom one.
+    pub fn retain_bottom(&mut self) {
+        self.0 &= 1
     }
 }
 
@@ -80,12 +142,6 @@ impl Index<usize> for Layers {
     }
 }
 
-impl IndexMut<usize> for Layers {
-    fn index_mut(& mut self, index: usize) -> &mut Self::Output {
-        &mut self.vec[index]
-    }
-}
-
 impl From<Vec<Layer>> for Layers {
     fn from(vec: Vec<Layer>) -> Self {
         let map: HashMap<String, usize> = vec.iter()
@@ -100,18 +156,33 @@ impl From<Vec<Layer>> for Layers {
 }
 
 impl Layers {
-    pub(crate) fn iter_mut(&mut self) -> IterMut<Layer> {
-        self.vec.iter_mut()
+    pub(crate) fn index_of(&self, name: &str) -> Option<usize> {
+        self.map.get(name).copied()
+    }
+
+    /// Returns the index of the referenced layer, or None if there is no such layer.
+    pub(crate) fn resolve(&self, lr: &LayerRef) -> Option<usize> {
+        match lr {
+            LayerRef::ByIndex(index) if *index < self.len() => Some(*index),
+            LayerRef::ByIndex(_) => None,
+            LayerRef::ByName(name) => self.index_of(name),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.vec.len()
     }
 
-    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut Layer> {
-        match self.map.get_mut(key) {
-            Some(idx) => Some(&mut self.vec[*idx]),
-            None => None,
+    /// Returns the layers that are active when remapping starts.
+    pub(crate) fn initially_active(&self) -> LayerSet {
+        let mut active = LayerSet::default();
+        for (i, layer) in self.vec.iter().enumerate() {
+            active.set(i, layer.active);
         }
+        active
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Layer> {
+    pub fn iter(&self) -> std::slice::Iter<'_, Layer> {
         self.vec.iter()
     }
 }
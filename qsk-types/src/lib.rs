@@ -8,8 +8,10 @@ pub mod errors;
 pub mod layer_composer;
 pub mod layers;
 pub mod control_code;
+pub mod mouse_keys;
 
 pub use layers::*;
 pub use layer_composer::*;
 pub use events::*;
 pub use control_code::*;
+pub use mouse_keys::{Acceleration, MouseKeysConfig};
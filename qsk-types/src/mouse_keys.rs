@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::control_code::ControlCode;
+use crate::events::{EventCode, InputEvent, KeyCode, KeyState, RelativeCode};
+
+/// How the speed of a held `MouseMove` or `MouseWheel` grows from its base step to `max_speed`
+/// times the base step over `MouseKeysConfig::time_to_max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Acceleration {
+    Constant,
+    Linear,
+    Quadratic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseKeysConfig {
+    /// Time between pointer movement steps while a `MouseMove` key is held.
+    pub interval: Duration,
+    /// Time between scroll steps while a `MouseWheel` key is held.
+    pub wheel_interval: Duration,
+    pub time_to_max: Duration,
+    pub max_speed: f64,
+    pub acceleration: Acceleration,
+}
+
+impl Default for MouseKeysConfig {
+    fn default() -> Self {
+        MouseKeysConfig {
+            interval: Duration::from_millis(16),
+            wheel_interval: Duration::from_millis(80),
+            time_to_max: Duration::from_millis(1000),
+            max_speed: 10.0,
+            acceleration: Acceleration::Quadratic,
+        }
+    }
+}
+
+impl MouseKeysConfig {
+    fn speed(&self, held: Duration) -> f64 {
+        let t = if self.time_to_max.is_zero() {
+            1.0
+        } else {
+            (held.as_secs_f64() / self.time_to_max.as_secs_f64()).min(1.0)
+        };
+        match self.acceleration {
+            Acceleration::Constant => 1.0,
+            Acceleration::Linear => 1.0 + (self.max_speed - 1.0) * t,
+            Acceleration::Quadratic => 1.0 + (self.max_speed - 1.0) * t * t,
+        }
+    }
+}
+
+struct Motion {
+    dx: i32,
+    dy: i32,
+    since: Instant,
+}
+
+/// Tracks held mouse keys and produces the relative events they emit over time.
+pub(crate) struct MouseKeys {
+    config: MouseKeysConfig,
+    moves: HashMap<KeyCode, Motion>,
+    wheels: HashMap<KeyCode, Motion>,
+    last_move: Instant,
+    last_wheel: Instant,
+}
+
+impl MouseKeys {
+    pub(crate) fn new(config: MouseKeysConfig) -> Self {
+        MouseKeys {
+            config,
+            moves: HashMap::new(),
+            wheels: HashMap::new(),
+            last_move: Instant::now(),
+            last_wheel: Instant::now(),
+        }
+    }
+
+    pub(crate) fn set_config(&mut self, config: MouseKeysConfig) {
+        self.config = config
+    }
+
+    /// Stops every motion, as if all mouse keys had been released.
+    pub(crate) fn release_all(&mut self) {
+        self.moves.clear();
+        self.wheels.clear();
+    }
+
+    /// Starts moving the pointer and returns the first step so that movement starts immediately
+    /// rather than after the first interval.
+    pub(crate) fn press_move(
+        &mut self,
+        key: KeyCode,
+        dx: i32,
+        dy: i32,
+        now: Instant,
+        time: SystemTime,
+        output: &mut Vec<ControlCode>,
+    ) {
+        self.moves.insert(key, Motion { dx, dy, since: now });
+        self.last_move = now;
+        relative_events(time, &[(RelativeCode::X, dx), (RelativeCode::Y, dy)], output)
+    }
+
+    pub(crate) fn press_wheel(
+        &mut self,
+        key: KeyCode,
+        notches: i32,
+        now: Instant,
+        time: SystemTime,
+        output: &mut Vec<ControlCode>,
+    ) {
+        self.wheels.insert(key, Motion { dx: 0, dy: notches, since: now });
+        self.last_wheel = now;
+        relative_events(time, &[(RelativeCode::Wheel, notches)], output)
+    }
+
+    pub(crate) fn release(&mut self, key: KeyCode) {
+        self.moves.remove(&key);
+        self.wheels.remove(&key);
+    }
+
+    /// How long the engine may wait before calling `tick`, if anything is held.
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        match (self.moves.is_empty(), self.wheels.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(self.config.interval),
+            (true, false) => Some(self.config.wheel_interval),
+            (false, false) => Some(self.config.interval.min(self.config.wheel_interval)),
+        }
+    }
+
+    /// Steps held keys whose interval has passed on the monotonic clock `now`, stamping the
+    /// events with the wall-clock `time`.
+    pub(crate) fn tick(&mut self, now: Instant, time: SystemTime, output: &mut Vec<ControlCode>) {
+        if !self.moves.is_empty() && elapsed(self.last_move, now) >= self.config.interval {
+            let (dx, dy) = self.step(&self.moves, now);
+            relative_events(time, &[(RelativeCode::X, dx), (RelativeCode::Y, dy)], output);
+            self.last_move = now;
+        }
+        if !self.wheels.is_empty() && elapsed(self.last_wheel, now) >= self.config.wheel_interval {
+            let (_, notches) = self.step(&self.wheels, now);
+            relative_events(time, &[(RelativeCode::Wheel, notches)], output);
+            self.last_wheel = now;
+        }
+    }
+
+    fn step(&self, motions: &HashMap<KeyCode, Motion>, now: Instant) -> (i32, i32) {
+        motions.values().fold((0, 0), |(x, y), m| {
+            let speed = self.config.speed(elapsed(m.since, now));
+            (
+                x + (m.dx as f64 * speed).round() as i32,
+                y + (m.dy as f64 * speed).round() as i32,
+            )
+        })
+    }
+}
+
+fn elapsed(since: Instant, now: Instant) -> Duration {
+    now.saturating_duration_since(since)
+}
+
+fn relative_events(time: SystemTime, axes: &[(RelativeCode, i32)], output: &mut Vec<ControlCode>) {
+    let events = axes.iter().filter(|(_, value)| *value != 0).map(|(code, value)| {
+        ControlCode::InputEvent(InputEvent {
+            time,
+            code: EventCode::Relative(*code),
+            state: KeyState::NotImplemented,
+            value: *value,
+            device: 0,
+        })
+    });
+    output.extend(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    #[test]
+    fn acceleration_curves() {
+        let mut config = MouseKeysConfig {
+            time_to_max: Duration::from_millis(1000),
+            max_speed: 5.0,
+            acceleration: Acceleration::Constant,
+            ..MouseKeysConfig::default()
+        };
+        let half = Duration::from_millis(500);
+        let full = Duration::from_millis(2000);
+
+        assert_that!(&config.speed(half), eq(1.0));
+        assert_that!(&config.speed(full), eq(1.0));
+
+        config.acceleration = Acceleration::Linear;
+        assert_that!(&config.speed(Duration::from_millis(0)), eq(1.0));
+        assert_that!(&config.speed(half), eq(3.0));
+        assert_that!(&config.speed(full), eq(5.0));
+
+        config.acceleration = Acceleration::Quadratic;
+        assert_that!(&config.speed(half), eq(2.0));
+        assert_that!(&config.speed(full), eq(5.0));
+    }
+}
@@ -1,4 +1,4 @@
-use std::panic;
+use std::thread;
 
 use async_std;
 use clap::{App, Arg, ArgMatches, 
@@ -17,7 +17,7 @@ pub fn get_clap_app() -> Result<ArgMatches, Box<dyn std::error::Error>> {
                 .short('p')
                 .long("passthrough")
                 .takes_value(false)
-                .help("Use passthrough mapper (for debugging purposes)"),
+                .help("Start out passing keys through unchanged; TogglePassthrough(), `qsk ctl toggle-passthrough` or SIGUSR1 switch to remapping"),
         )
         .arg(
             Arg::new("log-keys-to")
@@ -25,25 +25,233 @@ pub fn get_clap_app() -> Result<ArgMatches, Box<dyn std::error::Error>> {
                 .takes_value(true)
                 .help("Enables keylogging to specified file (for testing purposes)."),
         )
+        .arg(
+            Arg::new("latency-report")
+                .long("latency-report")
+                .takes_value(false)
+                .help("Print a report of the latency added by each stage of remapping on exit"),
+        )
+        .arg(
+            Arg::new("realtime")
+                .long("realtime")
+                .takes_value(false)
+                .help("Read, remap and write events with SCHED_FIFO priority and locked memory"),
+        )
+        .arg(
+            Arg::new("control-socket")
+                .long("control-socket")
+                .takes_value(true)
+                .help("Unix socket to take requests from `qsk ctl` on (default: $XDG_RUNTIME_DIR/qsk.sock)"),
+        )
+        .arg(
+            Arg::new("no-control-socket")
+                .long("no-control-socket")
+                .takes_value(false)
+                .conflicts_with("control-socket")
+                .help("Don't take requests from `qsk ctl`"),
+        )
+        .arg(
+            Arg::new("gamepad")
+                .short('g')
+                .long("gamepad")
+                .takes_value(false)
+                .help("Report gamepad and joystick axes as KC_ABS_* key presses"),
+        )
+        .arg(
+            Arg::new("deadzone")
+                .long("deadzone")
+                .takes_value(true)
+                .requires("gamepad")
+                .help("Fraction of an axis' range around its rest position that is ignored (default 0.15)"),
+        )
+        .arg(
+            Arg::new("threshold")
+                .long("threshold")
+                .takes_value(true)
+                .requires("gamepad")
+                .help("Fraction of an axis' range past the deadzone that presses its key (default 0.5)"),
+        )
+        .arg(
+            Arg::new("hysteresis")
+                .long("hysteresis")
+                .takes_value(true)
+                .requires("gamepad")
+                .help("Fraction of an axis' range below the threshold that releases its key (default 0.1)"),
+        )
+        .arg(
+            Arg::new("output-name")
+                .long("output-name")
+                .takes_value(true)
+                .help("Name of the virtual device (default: the name of the first input device)"),
+        )
+        .arg(
+            Arg::new("output-id")
+                .long("output-id")
+                .takes_value(true)
+                .help("Hexadecimal <vendor>:<product> ids of the virtual device, eg 1209:0001"),
+        )
+        .arg(
+            Arg::new("output-bus")
+                .long("output-bus")
+                .takes_value(true)
+                .help("Hexadecimal bus type of the virtual device, eg 3 for USB or 6 for virtual"),
+        )
+        .arg(
+            Arg::new("output-keys")
+                .long("output-keys")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .use_delimiter(true)
+                .help("Keys the virtual device supports, eg KC_A,KC_F13 (default: those of the input devices plus every key the layers can emit)"),
+        )
+        .arg(
+            Arg::new("device")
+                .short('d')
+                .long("device")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .help("Device selector, eg name:<name>, id:<vendor>:<product> or phys:<path>; may be repeated"),
+        )
         .arg(
             Arg::new("device-file")
-                .help("Input events file")
+                .help("Input events files or device selectors; events from all of them are merged into one virtual device")
                 .takes_value(true)
-                .required(true),
+                .multiple_values(true)
+                .required_unless_present("device"),
         )
-        .about("remap the keyboard represented by the specified device file");
+        .about("remap the keyboards represented by the specified device files");
 
     let listen = App::new("listen")
         .arg(
             Arg::new("device-file")
-                .help("Input events file")
+                .help("Input events file or device selector")
                 .takes_value(true)
                 .required(true),
         )
         .about("listen to and print events stdout");
 
-    let list_devices =
-        App::new("list-devices").about("list keyboard-type devices available for remapping");
+    let list_devices = App::new("list-devices")
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .takes_value(false)
+                .help("Print devices as JSON"),
+        )
+        .arg(
+            Arg::new("all")
+                .short('a')
+                .long("all")
+                .takes_value(false)
+                .help("List every input device, not only keyboards and mice"),
+        )
+        .arg(
+            Arg::new("min-keys")
+                .long("min-keys")
+                .takes_value(true)
+                .conflicts_with("all")
+                .help("List devices with more keys than this, eg macro pads (default 100)"),
+        )
+        .about("list keyboard and mouse devices available for remapping");
+
+    let inspect_device = App::new("inspect-device")
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .takes_value(false)
+                .help("Print the report as JSON"),
+        )
+        .arg(
+            Arg::new("device-file")
+                .help("Input events file or device selector")
+                .takes_value(true)
+                .required(true),
+        )
+        .about("report a device's supported events, keys and LEDs and their current state");
+
+    let import = App::new("import")
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .takes_value(true)
+                .required(true)
+                .possible_values(["kanata", "kmonad", "qmk"])
+                .help("Keymap format to import from"),
+        )
+        .arg(
+            Arg::new("keymap-file")
+                .help("Keymap file to import")
+                .takes_value(true)
+                .required(true),
+        )
+        .about("translate another keyboard remapper's keymap into a qsk remap! macro");
+
+    let export = App::new("export")
+        .arg(
+            Arg::new("layout")
+                .long("layout")
+                .takes_value(true)
+                .required(true)
+                .help("QMK layout whose key positions the keymap is exported for, eg LAYOUT_60_ansi"),
+        )
+        .arg(
+            Arg::new("keyboard")
+                .long("keyboard")
+                .takes_value(true)
+                .help("QMK keyboard name to record in the exported keymap"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .takes_value(true)
+                .help("File to write the exported keymap to (defaults to stdout)"),
+        )
+        .about("export the compiled-in keymap as a QMK keymap.json");
+
+    let ctl = App::new("ctl")
+        .arg(socket_arg())
+        .arg(
+            Arg::new("command")
+                .takes_value(true)
+                .required(true)
+                .possible_values([
+                    "status", "keymap", "activate", "deactivate", "toggle", "pause", "resume",
+                    "toggle-passthrough", "reset", "exit", "subscribe",
+                ])
+                .help("What to ask of the running remapper; `reset` returns to the startup layers, it doesn't reload the keymap, which is compiled in"),
+        )
+        .arg(
+            Arg::new("layer")
+                .takes_value(true)
+                .required_if_eq_any(&[
+                    ("command", "activate"),
+                    ("command", "deactivate"),
+                    ("command", "toggle"),
+                ])
+                .help("Name of the layer to activate, deactivate or toggle"),
+        )
+        .about("control a running `qsk remap`, eg to switch layers from a script");
+
+    let type_text = App::new("type")
+        .arg(socket_arg())
+        .arg(
+            Arg::new("text")
+                .takes_value(true)
+                .required(true)
+                .help("Text to type, as if on a US keyboard"),
+        )
+        .about("type text through a running `qsk remap`'s virtual keyboard");
+
+    let key = App::new("key")
+        .arg(socket_arg())
+        .arg(
+            Arg::new("chord")
+                .takes_value(true)
+                .required(true)
+                .multiple_values(true)
+                .help("Keys to press together joined by +, eg LCTRL+L; several chords are pressed in turn"),
+        )
+        .about("press keys through a running `qsk remap`'s virtual keyboard");
 
     let matches = App::new(crate_name!())
         .arg(
@@ -66,6 +274,12 @@ pub fn get_clap_app() -> Result<ArgMatches, Box<dyn std::error::Error>> {
         .subcommand(remap)
         .subcommand(listen)
         .subcommand(list_devices)
+        .subcommand(inspect_device)
+        .subcommand(import)
+        .subcommand(export)
+        .subcommand(ctl)
+        .subcommand(type_text)
+        .subcommand(key)
         .get_matches();
 
     let vs = matches.occurrences_of("verbose") as usize;
@@ -88,20 +302,27 @@ pub fn get_clap_app() -> Result<ArgMatches, Box<dyn std::error::Error>> {
     Ok(matches)
 }
 
+// The socket option of the subcommands that talk to a running remapper.
+fn socket_arg<'a>() -> Arg<'a> {
+    Arg::new("socket")
+        .short('s')
+        .long("socket")
+        .takes_value(true)
+        .help("Control socket of the running remapper (default: $XDG_RUNTIME_DIR/qsk.sock)")
+}
+
 fn setup_logger_fern(level: Option<log::LevelFilter>) -> Result<(), fern::InitError> {
     let mut dispatch = fern::Dispatch::new()
         .format(|out, message, record| {
-            let result = panic::catch_unwind(|| async_std::task::current());
-            let name = match result {
-                Ok(task) => match task.name() {
-                    Some(s) => s.to_string(),
-                    None => "root".to_string(),
-                },
-                Err(_) => "root".to_string(),
+            // events are read and transformed on threads of their own rather than in tasks
+            let task = async_std::task::try_current();
+            let name = match task.as_ref().and_then(|task| task.name()) {
+                Some(s) => s.to_string(),
+                None => thread::current().name().unwrap_or("root").to_string(),
             };
             out.finish(format_args!("{} {:?}: {}", record.level(), name, message))
         })
-        .chain(std::io::stdout())
+        .chain(std::io::stderr())
         .chain(fern::log_file("output.log")?);
 
     if let Some(lvl_fltr) = &level {
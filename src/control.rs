@@ -0,0 +1,506 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{lchown, FileTypeExt};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Sender};
+use std::thread::{self, JoinHandle};
+
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+
+use qsk_types::layers::{LayerInfo, LayerSet};
+
+use crate::device::cancel::Cancel;
+use crate::errors::{Error, Result};
+
+/// A request to a running `qsk remap`, sent over the control socket as a line of JSON, eg
+/// `{"command": "toggle", "layer": "presentation"}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// Reports whether remapping is paused and which layers are active.
+    Status,
+    /// Reports the keymap being remapped with.
+    Keymap,
+    Activate { layer: String },
+    Deactivate { layer: String },
+    Toggle { layer: String },
+    /// Passes events through unchanged until resumed.
+    Pause,
+    Resume,
+    /// Pauses if remapping, resumes if paused, like a key mapped to `TogglePassthrough()`.
+    TogglePassthrough,
+    /// Returns to the state remapping started in: the layers active then are again, and keys
+    /// held on the virtual device are released. This stands in for reloading the configuration,
+    /// which can't be done since the keymap is compiled in.
+    Reset,
+    Exit,
+    /// Types `text` on the virtual device as if on a US keyboard. The answer comes once the
+    /// keystrokes are queued; they are written one at a time between the user's own.
+    Type { text: String },
+    /// Presses and releases each chord in turn, eg `LCTRL+L`; see `inject::parse_chord`.
+    Key { chords: Vec<String> },
+    /// Answers with the current status, then keeps the connection open and sends an `Event`
+    /// whenever the status changes, eg for a status bar to show the active layers.
+    Subscribe,
+}
+
+/// The reply to a `Request`, a line of JSON such as `{"result": "layer", "layer": "presentation",
+/// "active": true}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Status { paused: bool, layers: Vec<LayerState> },
+    Keymap { layers: Vec<LayerInfo> },
+    Layer { layer: String, active: bool },
+    Error { message: String },
+    Event(Event),
+}
+
+/// A change of status sent to subscribers, eg `{"result": "event", "event": "layer", "layer":
+/// "navigation", "active": true}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A layer was activated or deactivated, whether by a key, a request or a reset.
+    Layer { layer: String, active: bool },
+    /// Remapping was paused or resumed.
+    Paused { paused: bool },
+}
+
+/// Subscribers are the connections that asked to be sent events.
+#[derive(Default)]
+pub struct Subscribers {
+    senders: Vec<Sender<Response>>,
+    // the layers as last published
+    names: Vec<String>,
+    active: LayerSet,
+}
+
+impl Subscribers {
+    pub fn add(&mut self, sender: Sender<Response>, layers: &[LayerInfo], active: LayerSet) {
+        self.names = layers.iter().map(|layer| layer.name.clone()).collect();
+        self.active = active;
+        self.senders.push(sender);
+    }
+
+    /// Sends `event` to every subscriber, forgetting those that have hung up.
+    pub fn publish(&mut self, event: Event) {
+        self.senders
+            .retain(|sender| sender.send(Response::Event(event.clone())).is_ok());
+    }
+
+    /// Publishes the layers activated or deactivated since the last call. Nothing is compared
+    /// unless somebody is listening.
+    pub fn update_layers(&mut self, active: LayerSet) {
+        if self.senders.is_empty() || active == self.active {
+            return;
+        }
+        let changed: Vec<Event> = self
+            .names
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.active.contains(*i) != active.contains(*i))
+            .map(|(i, name)| Event::Layer {
+                layer: name.clone(),
+                active: active.contains(i),
+            })
+            .collect();
+        self.active = active;
+        changed.into_iter().for_each(|event| self.publish(event));
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LayerState {
+    pub name: String,
+    pub active: bool,
+}
+
+impl From<LayerInfo> for LayerState {
+    fn from(info: LayerInfo) -> Self {
+        LayerState {
+            name: info.name,
+            active: info.active,
+        }
+    }
+}
+
+/// Where `qsk remap` listens and `qsk ctl` connects by default: the runtime directory of the user
+/// running qsk or, when run with sudo, of the user who ran sudo so that their scripts can
+/// connect.
+pub fn default_path() -> PathBuf {
+    let dir = match env::var("SUDO_UID") {
+        Ok(uid) => PathBuf::from(format!("/run/user/{}", uid)),
+        Err(_) => env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(env::temp_dir),
+    };
+    dir.join("qsk.sock")
+}
+
+/// ControlSocket is the Unix socket a running `qsk remap` takes requests on. Only its owner may
+/// connect, which when run with sudo is the user who ran sudo. The socket file is removed once
+/// it is dropped.
+pub struct ControlSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    pub fn bind(path: &Path) -> Result<ControlSocket> {
+        match fs::symlink_metadata(path) {
+            // qsk usually runs as root, so it mustn't be talked into removing anything else
+            Ok(meta) if !meta.file_type().is_socket() => {
+                return Err(Error::ControlSocketNotASocket(path.to_path_buf()))
+            }
+            // a socket nobody listens on is left over from a qsk that didn't exit cleanly
+            Ok(_) => match UnixStream::connect(path) {
+                Ok(_) => return Err(Error::ControlSocketInUse(path.to_path_buf())),
+                Err(_) => fs::remove_file(path)?,
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err.into()),
+        }
+        // the socket is created with its final mode rather than changed after the fact, when
+        // the path may have been replaced. The umask is the process', but nothing else creates
+        // files while the control socket is bound at startup.
+        let umask = unsafe { libc::umask(0o177) };
+        let bound = UnixListener::bind(path);
+        unsafe { libc::umask(umask) };
+        let socket = ControlSocket {
+            listener: bound?,
+            path: path.to_path_buf(),
+        };
+        // fchown would change the socket rather than the file connecting is checked against;
+        // lchown at least doesn't follow a symlink put in its place
+        if let (Ok(uid), Ok(gid)) = (env::var("SUDO_UID"), env::var("SUDO_GID")) {
+            lchown(path, uid.parse().ok(), gid.parse().ok())?;
+        }
+        socket.listener.set_nonblocking(true)?;
+        info!("listening for control requests on {}", path.display());
+        Ok(socket)
+    }
+
+    /// Serves requests on a thread of its own, sending each to the engine along with a channel
+    /// for the response. Every connection gets a thread too, since a script may keep one open
+    /// for as long as it likes. The socket is closed once `cancel` is cancelled.
+    pub fn serve<T>(self, requests: Sender<T>, cancel: Cancel) -> Result<Server>
+    where
+        T: From<(Request, Sender<Response>)> + Send + 'static,
+    {
+        let thread = thread::Builder::new()
+            .name("control".to_string())
+            .spawn(move || loop {
+                match self.listener.accept() {
+                    Ok((stream, _)) => {
+                        let requests = requests.clone();
+                        let spawned = thread::Builder::new()
+                            .name("control-client".to_string())
+                            .spawn(move || {
+                                if let Err(err) = serve_connection(stream, requests) {
+                                    debug!("control connection closed: {:?}", err);
+                                }
+                            });
+                        if let Err(err) = spawned {
+                            error!("error serving control connection: {:?}", err);
+                        }
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        if cancel.wait(self.listener.as_raw_fd()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        error!("error accepting control connection: {:?}", err);
+                        break;
+                    }
+                }
+            })?;
+        Ok(Server { thread })
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The thread serving a `ControlSocket`.
+pub struct Server {
+    thread: JoinHandle<()>,
+}
+
+impl Server {
+    /// Waits for the thread to end, which it does right after it has been cancelled.
+    pub fn join(self) {
+        if self.thread.join().is_err() {
+            error!("control thread panicked");
+        }
+    }
+}
+
+// Answers the requests on a connection, one line of JSON each, until the client hangs up or the
+// engine stops.
+fn serve_connection<T>(stream: UnixStream, requests: Sender<T>) -> Result<()>
+where
+    T: From<(Request, Sender<Response>)>,
+{
+    stream.set_nonblocking(false)?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                debug!("control request: {:?}", request);
+                let subscribe = request == Request::Subscribe;
+                let (sender, receiver) = channel();
+                if requests.send((request, sender).into()).is_err() {
+                    break;
+                }
+                // subscribers are sent events on the same channel as the status, until the
+                // client hangs up and writing fails
+                if subscribe {
+                    for response in receiver {
+                        write_line(&mut writer, &response)?;
+                    }
+                    break;
+                }
+                match receiver.recv() {
+                    Ok(response) => response,
+                    Err(_) => break,
+                }
+            }
+            Err(err) => Response::Error {
+                message: format!("invalid request: {}", err),
+            },
+        };
+        write_line(&mut writer, &response)?;
+    }
+    Ok(())
+}
+
+fn write_line<T: Serialize>(mut writer: impl Write, value: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    Ok(())
+}
+
+fn connect(path: &Path) -> Result<UnixStream> {
+    UnixStream::connect(path).map_err(|source| Error::ControlConnect {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Sends a request to the `qsk remap` listening at `path` and returns its response.
+pub fn request(path: &Path, request: &Request) -> Result<Response> {
+    let stream = connect(path)?;
+    write_line(&stream, request)?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Subscribes to the events of the `qsk remap` listening at `path`, returning the responses as
+/// they arrive: the status first, then events until it exits.
+pub fn subscribe(path: &Path) -> Result<impl Iterator<Item = Result<Response>>> {
+    let stream = connect(path)?;
+    write_line(&stream, &Request::Subscribe)?;
+    Ok(BufReader::new(stream)
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+    use std::sync::mpsc::Receiver;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    type Message = (Request, Sender<Response>);
+
+    fn socket_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("qsk-test-{}-{}.sock", name, std::process::id()))
+    }
+
+    fn layer(name: &str) -> LayerInfo {
+        LayerInfo {
+            name: name.to_string(),
+            active: false,
+            device: None,
+            keys: Vec::new(),
+        }
+    }
+
+    // Answers requests the way the engine would for a keymap without layers, pausing right after
+    // anybody subscribes.
+    fn answer(receiver: Receiver<Message>) {
+        let mut subscribers = Subscribers::default();
+        for (request, sender) in receiver {
+            let response = match request {
+                Request::Status => Response::Status {
+                    paused: false,
+                    layers: Vec::new(),
+                },
+                Request::Subscribe => {
+                    subscribers.add(sender.clone(), &[], LayerSet::default());
+                    sender.send(Response::Status { paused: false, layers: Vec::new() }).unwrap();
+                    subscribers.publish(Event::Paused { paused: true });
+                    continue;
+                }
+                request => Response::Error {
+                    message: format!("unexpected {:?}", request),
+                },
+            };
+            sender.send(response).unwrap();
+        }
+    }
+
+    #[test]
+    fn requests_parse_from_json_lines() {
+        let request: Request = serde_json::from_str(r#"{"command": "toggle", "layer": "presentation"}"#).unwrap();
+        assert_that!(&request, eq(Request::Toggle { layer: "presentation".to_string() }));
+        let request: Request = serde_json::from_str(r#"{"command": "pause"}"#).unwrap();
+        assert_that!(&request, eq(Request::Pause));
+        let request: Request =
+            serde_json::from_str(r#"{"command": "key", "chords": ["LCTRL+L"]}"#).unwrap();
+        assert_that!(&request, eq(Request::Key { chords: vec!["LCTRL+L".to_string()] }));
+
+        let response = serde_json::to_string(&Response::Layer {
+            layer: "presentation".to_string(),
+            active: true,
+        })
+        .unwrap();
+        assert_that!(&response.as_str(), eq(r#"{"result":"layer","layer":"presentation","active":true}"#));
+    }
+
+    #[test]
+    fn requests_are_answered_by_the_engine() -> Result<()> {
+        let path = socket_path("answered");
+        let cancel = Cancel::new()?;
+        let (sender, receiver) = channel::<Message>();
+        let server = ControlSocket::bind(&path)?.serve(sender, cancel.clone())?;
+        thread::spawn(move || answer(receiver));
+
+        let response = request(&path, &Request::Status)?;
+        assert_that!(&response, eq(Response::Status { paused: false, layers: Vec::new() }));
+
+        // malformed requests are answered without reaching the engine
+        let mut stream = UnixStream::connect(&path)?;
+        stream.write_all(b"{\"command\": \"dance\"}\n")?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        match serde_json::from_str(&line)? {
+            Response::Error { message } => assert!(message.starts_with("invalid request")),
+            other => panic!("expected an error, got {:?}", other),
+        }
+        drop(stream);
+
+        cancel.cancel();
+        server.join();
+        assert_that!(&path.exists(), eq(false));
+        Ok(())
+    }
+
+    #[test]
+    fn subscribers_are_sent_events() -> Result<()> {
+        let path = socket_path("subscribed");
+        let cancel = Cancel::new()?;
+        let (sender, receiver) = channel::<Message>();
+        let server = ControlSocket::bind(&path)?.serve(sender, cancel.clone())?;
+        thread::spawn(move || answer(receiver));
+
+        let mut responses = subscribe(&path)?;
+        let status = responses.next().unwrap()?;
+        assert_that!(&status, eq(Response::Status { paused: false, layers: Vec::new() }));
+        let event = responses.next().unwrap()?;
+        assert_that!(&event, eq(Response::Event(Event::Paused { paused: true })));
+        drop(responses);
+
+        cancel.cancel();
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn only_changed_layers_are_published() {
+        let (sender, receiver) = channel();
+        let mut subscribers = Subscribers::default();
+        let mut active = LayerSet::default();
+        active.set(0, true);
+        subscribers.add(sender, &[layer("base"), layer("navigation"), layer("numpad")], active);
+
+        subscribers.update_layers(active);
+        active.set(0, false);
+        active.set(2, true);
+        subscribers.update_layers(active);
+        drop(subscribers);
+
+        let events: Vec<Response> = receiver.iter().collect();
+        assert_that!(
+            &events,
+            eq(vec![
+                Response::Event(Event::Layer { layer: "base".to_string(), active: false }),
+                Response::Event(Event::Layer { layer: "numpad".to_string(), active: true }),
+            ])
+        );
+        let line = serde_json::to_string(&events[1]).unwrap();
+        assert_that!(
+            &line.as_str(),
+            eq(r#"{"result":"event","event":"layer","layer":"numpad","active":true}"#)
+        );
+    }
+
+    #[test]
+    fn sockets_in_use_are_not_taken_over() -> Result<()> {
+        let path = socket_path("in-use");
+        let socket = ControlSocket::bind(&path)?;
+        match ControlSocket::bind(&path) {
+            Err(Error::ControlSocketInUse(p)) => assert_that!(&p, eq(path.clone())),
+            other => panic!("expected the socket to be in use, got {:?}", other.map(|_| ())),
+        }
+
+        drop(socket);
+        assert_that!(&path.exists(), eq(false));
+
+        // a socket file nobody listens on is left over from a crash, and is bound anew
+        drop(UnixListener::bind(&path)?);
+        assert_that!(&path.exists(), eq(true));
+        drop(ControlSocket::bind(&path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn sockets_are_private_and_other_files_left_alone() -> Result<()> {
+        let path = socket_path("private");
+        let socket = ControlSocket::bind(&path)?;
+        let mode = fs::metadata(&path)?.permissions().mode();
+        assert_that!(&(mode & 0o777), eq(0o600));
+        drop(socket);
+
+        for create in [|p: &Path| fs::write(p, "keep"), |p: &Path| symlink("/etc/hostname", p)] {
+            create(&path)?;
+            match ControlSocket::bind(&path) {
+                Err(Error::ControlSocketNotASocket(p)) => assert_that!(&p, eq(path.clone())),
+                other => panic!("expected the file to be left alone, got {:?}", other.map(|_| ())),
+            }
+            assert_that!(&fs::symlink_metadata(&path).is_ok(), eq(true));
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use num::FromPrimitive;
+
+use crate::device::cancel::Cancel;
+use crate::device::gamepad::AxisRange;
+use crate::device::hotplug::{DeviceIdentity, Reconnect};
+use crate::device::info::{DeviceInfo, DeviceInspection};
+use crate::device::raw::{self, RawEvent};
+use crate::device::traits::{InputEventSink, InputEventSource};
+use crate::errors::Result;
+use crate::events::{AbsoluteCode, DeviceId, InputEvent, KeyCode, RelativeCode};
+
+/// Backend is implemented once per library used to talk to Linux input devices. It only moves
+/// `RawEvent`s to and from the kernel; `Device` and `VirtualDevice` build everything else on top
+/// of it so that as little as possible differs between backends. The conformance checks in
+/// `device::conformance` describe what a backend has to do, though only the in-memory fake is
+/// run against them.
+pub trait Backend: Sized + Send {
+    type Output: Output;
+
+    /// Opens the device node at `path` and grabs it, so that its events only reach qsk.
+    fn open(path: &Path) -> Result<Self>;
+
+    /// Returns the next event the device reported without blocking, or an error of kind
+    /// `WouldBlock` if there is none.
+    fn read(&mut self) -> Result<RawEvent>;
+
+    /// Blocks until the device has events to read or `cancel` is cancelled.
+    fn wait(&self, cancel: &Cancel) -> Result<()>;
+
+    fn identity(&self) -> DeviceIdentity;
+
+    /// Returns the ranges of the gamepad axes supported by the device.
+    fn axis_ranges(&self) -> HashMap<AbsoluteCode, AxisRange>;
+
+    /// Returns the keys the device supports.
+    fn keys(&self) -> Vec<u16>;
+
+    /// Reads the keys currently held down from the kernel.
+    fn pressed_keys(&self) -> Result<Vec<u16>>;
+
+    /// Creates a virtual device modeled on the device, identified as `config` says and supporting
+    /// `capabilities`.
+    fn create_output(&self, config: &OutputConfig, capabilities: &Capabilities) -> Result<Self::Output>;
+
+    /// Returns every input device on the system.
+    fn enumerate() -> Vec<DeviceInfo>;
+
+    fn inspect(path: &Path) -> Result<DeviceInspection>;
+}
+
+/// The writing side of a backend, a virtual device created through uinput.
+pub trait Output: Send {
+    /// Writes a batch of events, which ends with a `SYN_REPORT`.
+    fn write(&mut self, events: &[RawEvent]) -> Result<()>;
+}
+
+/// How the virtual device presents itself and which keys it supports. Unset fields are copied
+/// from the source device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OutputConfig {
+    pub name: Option<String>,
+    pub bustype: Option<u16>,
+    pub vendor: Option<u16>,
+    pub product: Option<u16>,
+    /// The keys the virtual device supports, replacing the default of those of the source device
+    /// plus `extra_keys`.
+    pub keys: Option<Vec<u16>>,
+    /// Keys to support besides those of the source device, eg every key the layers can emit.
+    pub extra_keys: Vec<u16>,
+}
+
+/// The codes the virtual device supports. Absolute axes, misc events and LEDs aren't listed;
+/// those of the source device are always copied so that they can be passed through.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Exactly the keys the virtual device supports.
+    pub keys: Vec<u16>,
+    /// Relative axes to support besides those of the source device.
+    pub relative_axes: Vec<u16>,
+}
+
+impl Capabilities {
+    fn new(source_keys: Vec<u16>, config: &OutputConfig) -> Capabilities {
+        let mut keys = match &config.keys {
+            Some(keys) => keys.clone(),
+            None => source_keys.into_iter().chain(config.extra_keys.iter().copied()).collect(),
+        };
+        // qsk's pseudo keys, eg KC_WHEEL_UP, can't be written to a device
+        keys.retain(|key| *key <= KeyCode::KC_MAX as u16);
+        keys.sort_unstable();
+        keys.dedup();
+        Capabilities {
+            keys,
+            // mouse keys need pointer axes even when the source device is a keyboard
+            relative_axes: [
+                RelativeCode::X,
+                RelativeCode::Y,
+                RelativeCode::Wheel,
+                RelativeCode::HWheel,
+            ]
+            .iter()
+            .map(|axis| *axis as u16)
+            .collect(),
+        }
+    }
+}
+
+/// A grabbed input device read through backend `B`.
+pub struct Device<B: Backend> {
+    backend: B,
+    id: DeviceId,
+    cancel: Cancel,
+}
+
+impl<B: Backend> Device<B> {
+    pub fn open(path: &Path) -> Result<Device<B>> {
+        Ok(Device {
+            backend: B::open(path)?,
+            id: 0,
+            cancel: Cancel::default(),
+        })
+    }
+
+    /// Sets the id attached to events read from the device when remapping several devices at
+    /// once.
+    pub fn with_id(mut self, id: DeviceId) -> Device<B> {
+        self.id = id;
+        self
+    }
+
+    /// Sets what stops a blocked `recv`, which then fails with `Error::Cancelled`.
+    pub fn with_cancel(mut self, cancel: Cancel) -> Device<B> {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn axis_ranges(&self) -> HashMap<AbsoluteCode, AxisRange> {
+        self.backend.axis_ranges()
+    }
+
+    pub fn keys(&self) -> Vec<u16> {
+        self.backend.keys()
+    }
+
+    pub fn new_uinput_device(&self, config: &OutputConfig) -> Result<VirtualDevice<B::Output>> {
+        let capabilities = Capabilities::new(self.backend.keys(), config);
+        Ok(VirtualDevice {
+            output: self.backend.create_output(config, &capabilities)?,
+        })
+    }
+
+    pub fn enumerate() -> Vec<DeviceInfo> {
+        B::enumerate()
+    }
+
+    pub fn inspect(path: &Path) -> Result<DeviceInspection> {
+        B::inspect(path)
+    }
+}
+
+impl<B: Backend> InputEventSource for Device<B> {
+    fn recv(&mut self) -> Result<InputEvent> {
+        loop {
+            match self.backend.read() {
+                Err(e) if e.is_would_block() => self.backend.wait(&self.cancel)?,
+                raw => return Ok(raw?.decode(self.id)),
+            }
+        }
+    }
+
+    fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+        let keys = self.backend.pressed_keys()?;
+        Ok(Some(keys.into_iter().filter_map(KeyCode::from_u16).collect()))
+    }
+}
+
+impl<B: Backend> Reconnect for Device<B> {
+    fn identity(&self) -> DeviceIdentity {
+        self.backend.identity()
+    }
+
+    fn reopen(&self, path: &Path) -> Result<Device<B>> {
+        Ok(Device::open(path)?.with_id(self.id).with_cancel(self.cancel.clone()))
+    }
+}
+
+/// The virtual device remapped events are written to.
+pub struct VirtualDevice<O: Output> {
+    output: O,
+}
+
+impl<O: Output> InputEventSink for VirtualDevice<O> {
+    fn send(&mut self, frame: &[InputEvent]) -> Result<()> {
+        let raw = raw::encode(frame);
+        match raw.is_empty() {
+            true => Ok(()),
+            false => self.output.write(&raw),
+        }
+    }
+}
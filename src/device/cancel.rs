@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::Arc;
+
+use crate::errors::{Error, Result};
+
+/// Cancel wakes up readers blocked on a device once the engine stops. Cancelling makes a pipe
+/// readable that `wait` polls together with the device, so that a reader doesn't stay blocked
+/// until the next keypress. A default `Cancel` is never cancelled.
+#[derive(Clone, Default)]
+pub struct Cancel {
+    pipe: Option<Arc<Pipe>>,
+}
+
+struct Pipe {
+    read: File,
+    write: File,
+}
+
+impl Cancel {
+    pub fn new() -> Result<Cancel> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) } == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let (read, write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+        Ok(Cancel {
+            pipe: Some(Arc::new(Pipe { read, write })),
+        })
+    }
+
+    /// Wakes up every reader waiting on a clone of this `Cancel`, now or later.
+    pub fn cancel(&self) {
+        // nothing ever reads from the pipe, so it stays readable
+        if let Some(pipe) = &self.pipe {
+            let _ = (&pipe.write).write(&[0]);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        match &self.pipe {
+            Some(pipe) => poll(&mut [pollfd(pipe.read.as_raw_fd())], 0).unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Blocks until `fd` is readable, returning `Error::Cancelled` once cancelled instead.
+    pub fn wait(&self, fd: RawFd) -> Result<()> {
+        let mut fds = vec![pollfd(fd)];
+        if let Some(pipe) = &self.pipe {
+            fds.push(pollfd(pipe.read.as_raw_fd()));
+        }
+        while !poll(&mut fds, -1)? {}
+        match fds.get(1) {
+            Some(cancel) if cancel.revents != 0 => Err(Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn pollfd(fd: RawFd) -> libc::pollfd {
+    libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    }
+}
+
+// Returns whether any of `fds` became ready within `timeout` milliseconds, -1 waiting for ever.
+fn poll(fds: &mut [libc::pollfd], timeout: i32) -> Result<bool> {
+    loop {
+        match unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) } {
+            -1 => match io::Error::last_os_error() {
+                e if e.kind() == io::ErrorKind::Interrupted => continue,
+                e => return Err(e.into()),
+            },
+            n => return Ok(n > 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    #[test]
+    fn cancel_wakes_up_waiters() -> Result<()> {
+        let cancel = Cancel::new()?;
+        // the read end of a pipe nobody writes to never becomes readable
+        let idle = Cancel::new()?;
+        let fd = idle.pipe.as_ref().unwrap().read.as_raw_fd();
+
+        let waiter = {
+            let cancel = cancel.clone();
+            thread::spawn(move || cancel.wait(fd))
+        };
+        assert_that!(&cancel.is_cancelled(), eq(false));
+        cancel.cancel();
+        assert_that!(&cancel.is_cancelled(), eq(true));
+        match waiter.join().unwrap() {
+            Err(Error::Cancelled) => (),
+            other => panic!("expected the wait to be cancelled, got {:?}", other),
+        }
+        drop(idle);
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Checks every backend has to pass. `conformance_tests!` turns them into tests for a backend
+//! that can be driven by a `Harness`, such as the in-memory fake in `device::fake`. The evdev-rs
+//! and evdev backends have no `Harness`, since driving them takes uinput and a udev that has
+//! created the device nodes, so they aren't checked here.
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use galvanic_assert::matchers::*;
+use galvanic_assert::*;
+
+use crate::device::backend::{Backend, Device, OutputConfig};
+use crate::device::cancel::Cancel;
+use crate::device::hotplug::Reconnect;
+use crate::device::raw::RawEvent;
+use crate::device::traits::{InputEventSink, InputEventSource};
+use crate::errors::{Error, Result};
+use crate::events::*;
+
+/// Controls the devices a backend under test sees.
+pub trait Harness {
+    type Backend: Backend;
+
+    /// Plugs in a device with the given keys that reports `events`, returning the path of its
+    /// device node. Its vendor, product and bus type are those of `SOURCE_ID`.
+    fn plug(name: &str, keys: &[KeyCode], events: &[RawEvent]) -> PathBuf;
+
+    fn unplug(path: &Path);
+
+    /// Makes the device at `path` report `keys` as held down.
+    fn press(path: &Path, keys: &[KeyCode]);
+
+    /// Returns what has been written to the virtual device modeled on the device at `path`.
+    fn written(path: &Path) -> Vec<RawEvent>;
+
+    /// Describes the virtual device modeled on the device at `path`, if one has been created.
+    fn output(path: &Path) -> Option<OutputDescription>;
+}
+
+/// The bus type, vendor and product of devices plugged in by a `Harness`.
+pub const SOURCE_ID: (u16, u16, u16) = (0x03, 0x1209, 0x0001);
+
+/// What a virtual device looks like to the rest of the system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputDescription {
+    pub name: String,
+    pub bustype: u16,
+    pub vendor: u16,
+    pub product: u16,
+    pub keys: Vec<u16>,
+    pub relative_axes: Vec<u16>,
+}
+
+fn raw(ms: u64, ty: u16, code: u16, value: i32) -> RawEvent {
+    RawEvent {
+        time: SystemTime::UNIX_EPOCH + Duration::from_millis(ms),
+        ty,
+        code,
+        value,
+    }
+}
+
+fn syn(ms: u64) -> RawEvent {
+    raw(ms, EV_SYN, 0, 0)
+}
+
+fn event(ms: u64, code: EventCode, state: KeyState, value: i32, device: DeviceId) -> InputEvent {
+    InputEvent {
+        time: SystemTime::UNIX_EPOCH + Duration::from_millis(ms),
+        code,
+        state,
+        value,
+        device,
+    }
+}
+
+pub fn opening_grabs_the_device<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[]);
+    let device = Device::<H::Backend>::open(&path)?;
+    assert_that!(&Device::<H::Backend>::open(&path).is_err(), eq(true));
+    drop(device);
+    assert_that!(&Device::<H::Backend>::open(&path).is_ok(), eq(true));
+    Ok(())
+}
+
+pub fn events_are_decoded_and_tagged<H: Harness>() -> Result<()> {
+    let path = H::plug(
+        "keyboard",
+        &[KeyCode::KC_A],
+        &[raw(1, EV_KEY, 30, 1), syn(1), raw(2, EV_REL, 0, -5), syn(2), raw(3, EV_KEY, 0x2fe, 2)],
+    );
+    let mut device = Device::<H::Backend>::open(&path)?.with_id(2);
+
+    let mut received = Vec::new();
+    for _ in 0..5 {
+        received.push(device.recv()?);
+    }
+    assert_that!(
+        &received,
+        eq(vec![
+            event(1, EventCode::KeyCode(KeyCode::KC_A), KeyState::Down, 1, 2),
+            event(1, EventCode::SynCode(SynCode::Report), KeyState::Up, 0, 2),
+            event(2, EventCode::Relative(RelativeCode::X), KeyState::NotImplemented, -5, 2),
+            event(2, EventCode::SynCode(SynCode::Report), KeyState::Up, 0, 2),
+            event(3, EventCode::Raw(EV_KEY, 0x2fe), KeyState::Held, 2, 2),
+        ])
+    );
+    Ok(())
+}
+
+pub fn blocked_reads_can_be_cancelled<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[]);
+    let cancel = Cancel::new()?;
+    let mut device = Device::<H::Backend>::open(&path)?.with_cancel(cancel.clone());
+
+    let received = thread::scope(|scope| {
+        let reader = scope.spawn(|| device.recv());
+        cancel.cancel();
+        reader.join().unwrap()
+    });
+    match received {
+        Err(Error::Cancelled) => (),
+        other => panic!("expected the read to be cancelled, got {:?}", other),
+    }
+    Ok(())
+}
+
+pub fn frames_are_written_with_one_syn_report<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[]);
+    let mut output = Device::<H::Backend>::open(&path)?.new_uinput_device(&OutputConfig::default())?;
+
+    output.send(&[
+        event(1, EventCode::KeyCode(KeyCode::KC_LEFTSHIFT), KeyState::Down, 1, 0),
+        event(1, EventCode::KeyCode(KeyCode::KC_A), KeyState::Down, 1, 0),
+    ])?;
+    output.send(&[])?;
+    output.send(&[
+        event(2, EventCode::Relative(RelativeCode::X), KeyState::NotImplemented, -3, 0),
+        event(2, EventCode::SynCode(SynCode::Report), KeyState::Up, 0, 0),
+    ])?;
+
+    assert_that!(
+        &H::written(&path),
+        eq(vec![raw(1, EV_KEY, 42, 1), raw(1, EV_KEY, 30, 1), syn(1), raw(2, EV_REL, 0, -3), syn(2)])
+    );
+    Ok(())
+}
+
+pub fn pressed_keys_are_read_from_the_device<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A, KeyCode::KC_B], &[]);
+    let mut device = Device::<H::Backend>::open(&path)?;
+    H::press(&path, &[KeyCode::KC_B]);
+    assert_that!(&device.pressed_keys()?, eq(Some(vec![KeyCode::KC_B])));
+    Ok(())
+}
+
+pub fn virtual_device_supports_source_and_extra_keys<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_B, KeyCode::KC_A], &[]);
+    let config = OutputConfig {
+        extra_keys: vec![KeyCode::KC_F13 as u16, KeyCode::KC_A as u16, KeyCode::KC_WHEEL_UP as u16],
+        ..OutputConfig::default()
+    };
+    let _output = Device::<H::Backend>::open(&path)?.new_uinput_device(&config)?;
+
+    let output = H::output(&path).expect("no virtual device created");
+    let (bustype, vendor, product) = SOURCE_ID;
+    assert_that!(&output.name.as_str(), eq("keyboard"));
+    assert_that!(&(output.bustype, output.vendor, output.product), eq((bustype, vendor, product)));
+    assert_that!(
+        &output.keys,
+        eq(vec![KeyCode::KC_A as u16, KeyCode::KC_B as u16, KeyCode::KC_F13 as u16])
+    );
+    for axis in [RelativeCode::X, RelativeCode::Y, RelativeCode::Wheel, RelativeCode::HWheel] {
+        assert_that!(&output.relative_axes.contains(&(axis as u16)), eq(true));
+    }
+    Ok(())
+}
+
+pub fn virtual_device_identity_and_keys_can_be_set<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[]);
+    let config = OutputConfig {
+        name: Some("qsk".to_string()),
+        bustype: Some(0x06),
+        vendor: Some(0x1234),
+        product: Some(0x5678),
+        keys: Some(vec![KeyCode::KC_F13 as u16]),
+        extra_keys: vec![KeyCode::KC_F14 as u16],
+    };
+    let _output = Device::<H::Backend>::open(&path)?.new_uinput_device(&config)?;
+
+    let output = H::output(&path).expect("no virtual device created");
+    assert_that!(&output.name.as_str(), eq("qsk"));
+    assert_that!(&(output.bustype, output.vendor, output.product), eq((0x06, 0x1234, 0x5678)));
+    assert_that!(&output.keys, eq(vec![KeyCode::KC_F13 as u16]));
+    Ok(())
+}
+
+pub fn unplugged_devices_are_disconnected<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[raw(1, EV_KEY, 30, 1)]);
+    let mut device = Device::<H::Backend>::open(&path)?;
+    device.recv()?;
+    H::unplug(&path);
+    match device.recv() {
+        Err(e) => assert_that!(&e.is_disconnected(), eq(true)),
+        Ok(e) => panic!("expected disconnect, got {:?}", e),
+    }
+    Ok(())
+}
+
+pub fn reopened_devices_keep_their_id<H: Harness>() -> Result<()> {
+    let path = H::plug("keyboard", &[KeyCode::KC_A], &[]);
+    let device = Device::<H::Backend>::open(&path)?.with_id(4);
+    H::unplug(&path);
+
+    let returned = H::plug("keyboard", &[KeyCode::KC_B], &[raw(1, EV_KEY, 48, 0)]);
+    let mut device = device.reopen(&returned)?;
+    assert_that!(&device.identity().name, eq(Some("keyboard".to_string())));
+    assert_that!(
+        &device.recv()?,
+        eq(event(1, EventCode::KeyCode(KeyCode::KC_B), KeyState::Up, 0, 4))
+    );
+    Ok(())
+}
+
+/// Generates a test for each check, run against the backend of the given `Harness`.
+macro_rules! conformance_tests {
+    ($harness:ty) => {
+        $crate::device::conformance::conformance_tests!(
+            $harness,
+            opening_grabs_the_device,
+            events_are_decoded_and_tagged,
+            blocked_reads_can_be_cancelled,
+            frames_are_written_with_one_syn_report,
+            pressed_keys_are_read_from_the_device,
+            virtual_device_supports_source_and_extra_keys,
+            virtual_device_identity_and_keys_can_be_set,
+            unplugged_devices_are_disconnected,
+            reopened_devices_keep_their_id
+        );
+    };
+    ($harness:ty, $($check:ident),*) => {
+        $(
+            #[test]
+            fn $check() -> $crate::errors::Result<()> {
+                $crate::device::conformance::$check::<$harness>()
+            }
+        )*
+    };
+}
+
+pub(crate) use conformance_tests;
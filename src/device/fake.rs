@@ -0,0 +1,230 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::device::backend::{Backend, Capabilities, Output, OutputConfig};
+use crate::device::cancel::Cancel;
+use crate::device::conformance::{Harness, OutputDescription, SOURCE_ID};
+use crate::device::gamepad::AxisRange;
+use crate::device::hotplug::DeviceIdentity;
+use crate::device::info::{DeviceInfo, DeviceInspection};
+use crate::device::raw::RawEvent;
+use crate::errors::{Error, Result};
+use crate::events::{AbsoluteCode, KeyCode};
+
+// A fake device node, standing in for a device under /dev/input.
+struct Node {
+    path: PathBuf,
+    name: String,
+    keys: Vec<u16>,
+    pressed: Vec<u16>,
+    events: VecDeque<RawEvent>,
+    plugged: bool,
+    grabbed: bool,
+    written: Vec<RawEvent>,
+    output: Option<OutputDescription>,
+}
+
+// Tests run in parallel, so every node gets its own path.
+static NODES: Mutex<Vec<Node>> = Mutex::new(Vec::new());
+static NEXT_NODE: AtomicUsize = AtomicUsize::new(0);
+
+fn nodes() -> MutexGuard<'static, Vec<Node>> {
+    NODES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn with_node<T>(path: &Path, f: impl FnOnce(&mut Node) -> Result<T>) -> Result<T> {
+    let mut nodes = nodes();
+    match nodes.iter_mut().find(|node| node.path == path && node.plugged) {
+        Some(node) => f(node),
+        // ENODEV, as reported by the kernel once a device has been unplugged
+        None => Err(Error::IO(io::Error::from_raw_os_error(19))),
+    }
+}
+
+/// An in-memory backend whose devices are plugged in and unplugged by tests.
+pub struct FakeBackend {
+    path: PathBuf,
+}
+
+impl Drop for FakeBackend {
+    fn drop(&mut self) {
+        let _ = with_node(&self.path, |node| {
+            node.grabbed = false;
+            Ok(())
+        });
+    }
+}
+
+impl Backend for FakeBackend {
+    type Output = FakeOutput;
+
+    fn open(path: &Path) -> Result<FakeBackend> {
+        with_node(path, |node| match node.grabbed {
+            // EBUSY, as reported by EVIOCGRAB for a device that's already grabbed
+            true => Err(Error::IO(io::Error::from_raw_os_error(16))),
+            false => {
+                node.grabbed = true;
+                Ok(FakeBackend {
+                    path: path.to_path_buf(),
+                })
+            }
+        })
+    }
+
+    fn read(&mut self) -> Result<RawEvent> {
+        with_node(&self.path, |node| {
+            node.events
+                .pop_front()
+                .ok_or_else(|| Error::IO(io::ErrorKind::WouldBlock.into()))
+        })
+    }
+
+    // there is no file descriptor to wait on, so the node is polled
+    fn wait(&self, cancel: &Cancel) -> Result<()> {
+        while with_node(&self.path, |node| Ok(node.events.is_empty()))? {
+            if cancel.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+
+    fn identity(&self) -> DeviceIdentity {
+        let name = with_node(&self.path, |node| Ok(node.name.clone())).ok();
+        DeviceIdentity { name, phys: None }
+    }
+
+    fn axis_ranges(&self) -> HashMap<AbsoluteCode, AxisRange> {
+        HashMap::new()
+    }
+
+    fn keys(&self) -> Vec<u16> {
+        with_node(&self.path, |node| Ok(node.keys.clone())).unwrap_or_default()
+    }
+
+    fn pressed_keys(&self) -> Result<Vec<u16>> {
+        with_node(&self.path, |node| Ok(node.pressed.clone()))
+    }
+
+    fn create_output(&self, config: &OutputConfig, capabilities: &Capabilities) -> Result<FakeOutput> {
+        let (bustype, vendor, product) = SOURCE_ID;
+        with_node(&self.path, |node| {
+            node.output = Some(OutputDescription {
+                name: config.name.clone().unwrap_or_else(|| node.name.clone()),
+                bustype: config.bustype.unwrap_or(bustype),
+                vendor: config.vendor.unwrap_or(vendor),
+                product: config.product.unwrap_or(product),
+                keys: capabilities.keys.clone(),
+                relative_axes: capabilities.relative_axes.clone(),
+            });
+            Ok(FakeOutput {
+                path: self.path.clone(),
+            })
+        })
+    }
+
+    fn enumerate() -> Vec<DeviceInfo> {
+        nodes()
+            .iter()
+            .filter(|node| node.plugged)
+            .map(|node| DeviceInfo {
+                path: node.path.clone(),
+                name: Some(node.name.clone()),
+                phys: None,
+                vendor: SOURCE_ID.1,
+                product: SOURCE_ID.2,
+                version: 0,
+                key_count: node.keys.len(),
+                relative_axes: false,
+            })
+            .collect()
+    }
+
+    fn inspect(path: &Path) -> Result<DeviceInspection> {
+        let info = FakeBackend::enumerate()
+            .into_iter()
+            .find(|info| info.path == path)
+            .ok_or_else(|| Error::IO(io::Error::from_raw_os_error(19)))?;
+        Ok(DeviceInspection {
+            info,
+            event_types: Vec::new(),
+            keys: Vec::new(),
+            leds: Vec::new(),
+            pressed_keys: Vec::new(),
+            lit_leds: Vec::new(),
+        })
+    }
+}
+
+pub struct FakeOutput {
+    path: PathBuf,
+}
+
+impl Output for FakeOutput {
+    fn write(&mut self, events: &[RawEvent]) -> Result<()> {
+        // the virtual device outlives the node it was modeled on
+        let mut nodes = nodes();
+        if let Some(node) = nodes.iter_mut().find(|node| node.path == self.path) {
+            node.written.extend_from_slice(events);
+        }
+        Ok(())
+    }
+}
+
+impl Harness for FakeBackend {
+    type Backend = FakeBackend;
+
+    fn plug(name: &str, keys: &[KeyCode], events: &[RawEvent]) -> PathBuf {
+        let path = PathBuf::from(format!("/fake/input/event{}", NEXT_NODE.fetch_add(1, Ordering::SeqCst)));
+        nodes().push(Node {
+            path: path.clone(),
+            name: name.to_string(),
+            keys: keys.iter().map(|key| *key as u16).collect(),
+            pressed: Vec::new(),
+            events: events.iter().copied().collect(),
+            plugged: true,
+            grabbed: false,
+            written: Vec::new(),
+            output: None,
+        });
+        path
+    }
+
+    fn unplug(path: &Path) {
+        nodes()
+            .iter_mut()
+            .filter(|node| node.path == path)
+            .for_each(|node| node.plugged = false);
+    }
+
+    fn press(path: &Path, keys: &[KeyCode]) {
+        nodes()
+            .iter_mut()
+            .filter(|node| node.path == path)
+            .for_each(|node| node.pressed = keys.iter().map(|key| *key as u16).collect());
+    }
+
+    fn written(path: &Path) -> Vec<RawEvent> {
+        nodes()
+            .iter()
+            .find(|node| node.path == path)
+            .map_or_else(Vec::new, |node| node.written.clone())
+    }
+
+    fn output(path: &Path) -> Option<OutputDescription> {
+        nodes()
+            .iter()
+            .find(|node| node.path == path)
+            .and_then(|node| node.output.clone())
+    }
+}
+
+mod tests {
+    crate::device::conformance::conformance_tests!(super::FakeBackend);
+}
@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+use log::warn;
+
+use crate::device::traits::InputEventSource;
+use crate::errors::Result;
+use crate::events::{EventCode, InputEvent, KeyCode, KeyState, SynCode};
+
+/// The events of one report from a device, up to but not including the `SYN_REPORT` ending it.
+/// Events in a frame happened at the same time, eg a modifier and a key of a chord or the X and Y
+/// movement of a mouse, and are written out together.
+pub type Frame = Vec<InputEvent>;
+
+/// FrameReader reads whole frames from an input source. When the kernel reports with
+/// `SYN_DROPPED` that its buffer overflowed, the partial frames around it are discarded and the
+/// keys held down are read from the device again; the difference to what was last reported is
+/// returned as a frame of its own so that no key stays stuck.
+pub struct FrameReader<S: InputEventSource> {
+    source: S,
+    pressed: HashSet<KeyCode>,
+}
+
+impl<S: InputEventSource> FrameReader<S> {
+    pub fn new(source: S) -> FrameReader<S> {
+        FrameReader {
+            source,
+            pressed: HashSet::new(),
+        }
+    }
+
+    pub fn next_frame(&mut self) -> Result<Frame> {
+        let mut frame = Frame::new();
+        loop {
+            let e = self.source.recv()?;
+            match e.code {
+                EventCode::SynCode(SynCode::Report) if frame.is_empty() => continue,
+                EventCode::SynCode(SynCode::Report) => break,
+                EventCode::SynCode(SynCode::Dropped) => {
+                    warn!("device {} dropped events, resynchronizing", e.device);
+                    self.skip_to_report()?;
+                    frame = self.resync(&e)?;
+                    if !frame.is_empty() {
+                        break;
+                    }
+                }
+                _ => frame.push(e),
+            }
+        }
+        self.track(&frame);
+        Ok(frame)
+    }
+
+    // Events following SYN_DROPPED are incomplete up to and including the next SYN_REPORT.
+    fn skip_to_report(&mut self) -> Result<()> {
+        while self.source.recv()?.code != EventCode::SynCode(SynCode::Report) {}
+        Ok(())
+    }
+
+    // Releases the keys no longer held and presses the ones newly held. Without a way to read the
+    // device's state every key is released.
+    fn resync(&mut self, dropped: &InputEvent) -> Result<Frame> {
+        let held: HashSet<KeyCode> = match self.source.pressed_keys()? {
+            Some(keys) => keys.into_iter().collect(),
+            None => HashSet::new(),
+        };
+        let event = |kc: &KeyCode, state: KeyState| InputEvent {
+            time: SystemTime::now(),
+            code: EventCode::KeyCode(*kc),
+            state,
+            value: state as i32,
+            device: dropped.device,
+        };
+        let released = self.pressed.difference(&held).map(|kc| event(kc, KeyState::Up));
+        let pressed = held.difference(&self.pressed).map(|kc| event(kc, KeyState::Down));
+        Ok(released.chain(pressed).collect())
+    }
+
+    fn track(&mut self, frame: &Frame) {
+        for e in frame {
+            if let EventCode::KeyCode(kc) = e.code {
+                match e.state {
+                    KeyState::Down | KeyState::Held => self.pressed.insert(kc),
+                    _ => self.pressed.remove(&kc),
+                };
+            }
+        }
+    }
+}
+
+/// FrameBuilder groups output events into frames. An event for a code that is already part of the
+/// current frame, eg the release of a key that was just pressed, starts a new frame so that each
+/// press and release reaches applications in a report of its own, while chords stay together.
+#[derive(Default)]
+pub struct FrameBuilder {
+    frames: Vec<Frame>,
+}
+
+impl FrameBuilder {
+    pub fn push(&mut self, e: InputEvent) {
+        if let EventCode::SynCode(SynCode::Report) = e.code {
+            return;
+        }
+        match self.frames.last_mut() {
+            Some(frame) if !frame.iter().any(|other| other.code == e.code) => frame.push(e),
+            _ => self.frames.push(vec![e]),
+        }
+    }
+
+    pub fn finish(self) -> Vec<Frame> {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+    use crate::errors::Error;
+    use crate::events::KeyCode::*;
+
+    struct FakeSource {
+        events: VecDeque<InputEvent>,
+        pressed: Vec<KeyCode>,
+    }
+
+    impl InputEventSource for FakeSource {
+        fn recv(&mut self) -> Result<InputEvent> {
+            self.events.pop_front().ok_or(Error::NoEvents)
+        }
+
+        fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+            Ok(Some(self.pressed.clone()))
+        }
+    }
+
+    fn event(code: EventCode, state: KeyState) -> InputEvent {
+        InputEvent {
+            time: SystemTime::UNIX_EPOCH,
+            code,
+            state,
+            value: state as i32,
+            device: 0,
+        }
+    }
+
+    fn key(kc: KeyCode, state: KeyState) -> InputEvent {
+        event(EventCode::KeyCode(kc), state)
+    }
+
+    fn syn(code: SynCode) -> InputEvent {
+        event(EventCode::SynCode(code), KeyState::Up)
+    }
+
+    fn codes(frame: &Frame) -> Vec<(EventCode, KeyState)> {
+        let mut codes: Vec<(EventCode, KeyState)> = frame.iter().map(|e| (e.code, e.state)).collect();
+        codes.sort_by_key(|(code, _)| format!("{:?}", code));
+        codes
+    }
+
+    #[test]
+    fn frames_end_at_syn_report() -> Result<()> {
+        let mut reader = FrameReader::new(FakeSource {
+            events: vec![
+                key(KC_LEFTSHIFT, KeyState::Down),
+                key(KC_A, KeyState::Down),
+                syn(SynCode::Report),
+                syn(SynCode::Report),
+                key(KC_A, KeyState::Up),
+                syn(SynCode::Report),
+            ]
+            .into(),
+            pressed: Vec::new(),
+        });
+        assert_that!(
+            &reader.next_frame()?,
+            eq(vec![key(KC_LEFTSHIFT, KeyState::Down), key(KC_A, KeyState::Down)])
+        );
+        assert_that!(&reader.next_frame()?, eq(vec![key(KC_A, KeyState::Up)]));
+        Ok(())
+    }
+
+    #[test]
+    fn resync_after_dropped_events() -> Result<()> {
+        let mut reader = FrameReader::new(FakeSource {
+            events: vec![
+                key(KC_A, KeyState::Down),
+                key(KC_B, KeyState::Down),
+                syn(SynCode::Report),
+                key(KC_C, KeyState::Down),
+                syn(SynCode::Dropped),
+                key(KC_A, KeyState::Up),
+                syn(SynCode::Report),
+                key(KC_D, KeyState::Down),
+                syn(SynCode::Report),
+            ]
+            .into(),
+            // A was released and C pressed while events were being dropped
+            pressed: vec![KC_B, KC_C],
+        });
+        reader.next_frame()?;
+        assert_that!(
+            &codes(&reader.next_frame()?),
+            eq(vec![
+                (EventCode::KeyCode(KC_A), KeyState::Up),
+                (EventCode::KeyCode(KC_C), KeyState::Down),
+            ])
+        );
+        assert_that!(&reader.next_frame()?, eq(vec![key(KC_D, KeyState::Down)]));
+        Ok(())
+    }
+
+    #[test]
+    fn taps_are_split_into_separate_frames() {
+        let mut builder = FrameBuilder::default();
+        for e in [
+            key(KC_LEFTCTRL, KeyState::Down),
+            key(KC_C, KeyState::Down),
+            syn(SynCode::Report),
+            key(KC_C, KeyState::Up),
+            key(KC_LEFTCTRL, KeyState::Up),
+        ] {
+            builder.push(e);
+        }
+        assert_that!(
+            &builder.finish(),
+            eq(vec![
+                vec![key(KC_LEFTCTRL, KeyState::Down), key(KC_C, KeyState::Down)],
+                vec![key(KC_C, KeyState::Up), key(KC_LEFTCTRL, KeyState::Up)],
+            ])
+        );
+    }
+}
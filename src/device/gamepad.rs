@@ -0,0 +1,336 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::device::traits::InputEventSource;
+use crate::errors::Result;
+use crate::events::{AbsoluteCode, EventCode, InputEvent, KeyCode, KeyState};
+
+// Axes that can be remapped along with the pseudo key codes reported for their negative and
+// positive directions.
+const AXIS_KEYS: [(AbsoluteCode, KeyCode, KeyCode); 12] = [
+    (AbsoluteCode::X, KeyCode::KC_ABS_X_MINUS, KeyCode::KC_ABS_X_PLUS),
+    (AbsoluteCode::Y, KeyCode::KC_ABS_Y_MINUS, KeyCode::KC_ABS_Y_PLUS),
+    (AbsoluteCode::Z, KeyCode::KC_ABS_Z_MINUS, KeyCode::KC_ABS_Z_PLUS),
+    (AbsoluteCode::RX, KeyCode::KC_ABS_RX_MINUS, KeyCode::KC_ABS_RX_PLUS),
+    (AbsoluteCode::RY, KeyCode::KC_ABS_RY_MINUS, KeyCode::KC_ABS_RY_PLUS),
+    (AbsoluteCode::RZ, KeyCode::KC_ABS_RZ_MINUS, KeyCode::KC_ABS_RZ_PLUS),
+    (AbsoluteCode::Throttle, KeyCode::KC_ABS_THROTTLE_MINUS, KeyCode::KC_ABS_THROTTLE_PLUS),
+    (AbsoluteCode::Rudder, KeyCode::KC_ABS_RUDDER_MINUS, KeyCode::KC_ABS_RUDDER_PLUS),
+    (AbsoluteCode::Gas, KeyCode::KC_ABS_GAS_MINUS, KeyCode::KC_ABS_GAS_PLUS),
+    (AbsoluteCode::Brake, KeyCode::KC_ABS_BRAKE_MINUS, KeyCode::KC_ABS_BRAKE_PLUS),
+    (AbsoluteCode::Hat0X, KeyCode::KC_ABS_HAT0X_MINUS, KeyCode::KC_ABS_HAT0X_PLUS),
+    (AbsoluteCode::Hat0Y, KeyCode::KC_ABS_HAT0Y_MINUS, KeyCode::KC_ABS_HAT0Y_PLUS),
+];
+
+/// The axes a `Gamepad` turns into key presses.
+pub fn axes() -> impl Iterator<Item = AbsoluteCode> {
+    AXIS_KEYS.iter().map(|(code, _, _)| *code)
+}
+
+/// Distances applied to every axis, as fractions of the way from the axis' rest position to the
+/// end of its range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisConfig {
+    /// Movement this close to the rest position is ignored so that stick drift doesn't count
+    /// towards the threshold.
+    pub deadzone: f64,
+    /// How far past the deadzone an axis has to be pushed to press its key.
+    pub threshold: f64,
+    /// How far below the threshold a pushed axis has to return to release its key, so that an
+    /// axis hovering around the threshold doesn't chatter.
+    pub hysteresis: f64,
+}
+
+impl Default for AxisConfig {
+    fn default() -> Self {
+        AxisConfig {
+            deadzone: 0.15,
+            threshold: 0.5,
+            hysteresis: 0.1,
+        }
+    }
+}
+
+/// The range an axis reports values in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisRange {
+    pub min: i32,
+    pub max: i32,
+    pub rest: i32,
+}
+
+impl AxisRange {
+    /// Sticks and hats rest in the middle of their range while triggers and pedals rest at one end
+    /// of it. An axis reporting either end when the device is opened is taken to be the latter.
+    pub fn new(min: i32, max: i32, value: i32) -> AxisRange {
+        let rest = if value == min || value == max {
+            value
+        } else {
+            min + (max - min) / 2
+        };
+        AxisRange { min, max, rest }
+    }
+
+    // Maps a value onto -1.0..=1.0, with 0.0 being the rest position.
+    fn position(&self, value: i32) -> f64 {
+        let (from, to) = if value >= self.rest {
+            (self.rest, self.max)
+        } else {
+            (self.rest, self.min)
+        };
+        if from == to {
+            return 0.0;
+        }
+        let position = (value - from) as f64 / (to - from) as f64;
+        match value >= self.rest {
+            true => position.min(1.0),
+            false => -position.min(1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Minus,
+    Neutral,
+    Plus,
+}
+
+struct Axis {
+    range: AxisRange,
+    minus: KeyCode,
+    plus: KeyCode,
+    direction: Direction,
+}
+
+impl Axis {
+    fn direction(&self, config: &AxisConfig, value: i32) -> Direction {
+        let position = self.range.position(value);
+        let magnitude =
+            ((position.abs() - config.deadzone) / (1.0 - config.deadzone).max(f64::EPSILON)).max(0.0);
+        let pushed = if position > 0.0 {
+            Direction::Plus
+        } else {
+            Direction::Minus
+        };
+        let threshold = match self.direction == pushed {
+            true => config.threshold - config.hysteresis,
+            false => config.threshold,
+        };
+        if magnitude > 0.0 && magnitude >= threshold {
+            pushed
+        } else {
+            Direction::Neutral
+        }
+    }
+
+    fn key(&self, direction: Direction) -> Option<KeyCode> {
+        match direction {
+            Direction::Minus => Some(self.minus),
+            Direction::Neutral => None,
+            Direction::Plus => Some(self.plus),
+        }
+    }
+}
+
+/// Gamepad wraps the input source of a game controller or joystick and reports each of its
+/// sticks, triggers and hats being pushed past the threshold as a press of the corresponding
+/// `KC_ABS_*` pseudo key, so that they can be remapped by ordinary layers along with the
+/// controller's buttons. Events from axes without a known range are passed through unchanged.
+pub struct Gamepad<S: InputEventSource> {
+    inner: S,
+    config: AxisConfig,
+    axes: HashMap<AbsoluteCode, Axis>,
+    pending: VecDeque<InputEvent>,
+}
+
+impl<S: InputEventSource> Gamepad<S> {
+    pub fn new(inner: S, ranges: HashMap<AbsoluteCode, AxisRange>, config: AxisConfig) -> Self {
+        let axes = AXIS_KEYS
+            .iter()
+            .filter_map(|(code, minus, plus)| {
+                ranges.get(code).map(|range| {
+                    (
+                        *code,
+                        Axis {
+                            range: *range,
+                            minus: *minus,
+                            plus: *plus,
+                            direction: Direction::Neutral,
+                        },
+                    )
+                })
+            })
+            .collect();
+        Gamepad {
+            inner,
+            config,
+            axes,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn update_axis(&mut self, code: AbsoluteCode, e: InputEvent) {
+        let axis = match self.axes.get_mut(&code) {
+            Some(axis) => axis,
+            None => return,
+        };
+        let direction = axis.direction(&self.config, e.value);
+        if direction == axis.direction {
+            return;
+        }
+        if let Some(key) = axis.key(axis.direction) {
+            self.pending.push_back(key_event(&e, key, KeyState::Up));
+        }
+        if let Some(key) = axis.key(direction) {
+            self.pending.push_back(key_event(&e, key, KeyState::Down));
+        }
+        axis.direction = direction;
+    }
+}
+
+impl<S: InputEventSource> InputEventSource for Gamepad<S> {
+    fn recv(&mut self) -> Result<InputEvent> {
+        loop {
+            if let Some(e) = self.pending.pop_front() {
+                return Ok(e);
+            }
+            let e = self.inner.recv()?;
+            match e.code {
+                EventCode::Absolute(code) if self.axes.contains_key(&code) => {
+                    self.update_axis(code, e)
+                }
+                _ => return Ok(e),
+            }
+        }
+    }
+
+    fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+        let axes = self.axes.values().filter_map(|axis| axis.key(axis.direction));
+        Ok(self.inner.pressed_keys()?.map(|keys| keys.into_iter().chain(axes).collect()))
+    }
+}
+
+fn key_event(e: &InputEvent, key: KeyCode, state: KeyState) -> InputEvent {
+    InputEvent {
+        time: e.time,
+        code: EventCode::KeyCode(key),
+        state,
+        value: state as i32,
+        device: e.device,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+    use maplit::hashmap;
+
+    use super::*;
+    use crate::errors::Error;
+    use crate::events::KeyCode::*;
+
+    struct FakeSource(VecDeque<InputEvent>);
+
+    impl InputEventSource for FakeSource {
+        fn recv(&mut self) -> Result<InputEvent> {
+            self.0.pop_front().ok_or(Error::NoEvents)
+        }
+    }
+
+    fn abs(code: AbsoluteCode, value: i32) -> InputEvent {
+        InputEvent {
+            time: SystemTime::UNIX_EPOCH,
+            code: EventCode::Absolute(code),
+            state: KeyState::NotImplemented,
+            value,
+            device: 0,
+        }
+    }
+
+    fn key(key: KeyCode, state: KeyState) -> InputEvent {
+        InputEvent {
+            time: SystemTime::UNIX_EPOCH,
+            code: EventCode::KeyCode(key),
+            state,
+            value: state as i32,
+            device: 0,
+        }
+    }
+
+    fn drain(gamepad: &mut Gamepad<FakeSource>) -> Vec<InputEvent> {
+        let mut output = Vec::new();
+        while let Ok(e) = gamepad.recv() {
+            output.push(e);
+        }
+        output
+    }
+
+    #[test]
+    fn axis_range_rest_position() {
+        let stick = AxisRange::new(0, 255, 128);
+        assert_that!(&stick.rest, eq(127));
+        assert_that!(&stick.position(255), eq(1.0));
+        assert_that!(&stick.position(0), eq(-1.0));
+
+        let trigger = AxisRange::new(0, 255, 0);
+        assert_that!(&trigger.rest, eq(0));
+        assert_that!(&trigger.position(255), eq(1.0));
+    }
+
+    #[test]
+    fn stick_thresholds_and_hysteresis() {
+        let events = vec![
+            abs(AbsoluteCode::X, 10),   // inside the deadzone
+            abs(AbsoluteCode::X, 50),   // past the threshold
+            abs(AbsoluteCode::X, 45),   // within the hysteresis
+            abs(AbsoluteCode::X, 30),   // released
+            abs(AbsoluteCode::X, -100), // straight to the other side
+            abs(AbsoluteCode::X, 0),
+        ];
+        let mut gamepad = Gamepad::new(
+            FakeSource(events.into()),
+            hashmap! { AbsoluteCode::X => AxisRange::new(-100, 100, 0) },
+            AxisConfig {
+                deadzone: 0.2,
+                threshold: 0.25,
+                hysteresis: 0.1,
+            },
+        );
+        assert_that!(
+            &drain(&mut gamepad),
+            eq(vec![
+                key(KC_ABS_X_PLUS, KeyState::Down),
+                key(KC_ABS_X_PLUS, KeyState::Up),
+                key(KC_ABS_X_MINUS, KeyState::Down),
+                key(KC_ABS_X_MINUS, KeyState::Up),
+            ])
+        );
+    }
+
+    #[test]
+    fn hats_buttons_and_unknown_axes() {
+        let events = vec![
+            abs(AbsoluteCode::Hat0Y, -1),
+            key(KC_BTN_SOUTH, KeyState::Down),
+            abs(AbsoluteCode::Hat0Y, 0),
+            abs(AbsoluteCode::Z, 200),
+        ];
+        let mut gamepad = Gamepad::new(
+            FakeSource(events.into()),
+            hashmap! { AbsoluteCode::Hat0Y => AxisRange::new(-1, 1, 0) },
+            AxisConfig::default(),
+        );
+        assert_that!(
+            &drain(&mut gamepad),
+            eq(vec![
+                key(KC_ABS_HAT0Y_MINUS, KeyState::Down),
+                key(KC_BTN_SOUTH, KeyState::Down),
+                key(KC_ABS_HAT0Y_MINUS, KeyState::Up),
+                abs(AbsoluteCode::Z, 200),
+            ])
+        );
+    }
+}
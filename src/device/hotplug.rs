@@ -0,0 +1,360 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use inotify::{Inotify, WatchMask};
+use log::{debug, info, warn};
+
+use crate::device::cancel::Cancel;
+use crate::device::traits::InputEventSource;
+use crate::errors::Result;
+use crate::events::{EventCode, InputEvent, KeyCode, KeyState, SynCode};
+
+/// Where the kernel creates input device nodes.
+pub const INPUT_DIR: &str = "/dev/input";
+
+/// What a device is recognized by when it's plugged back in. Device nodes are numbered in the
+/// order devices appear, so a returning device usually gets a different path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub name: Option<String>,
+    pub phys: Option<String>,
+}
+
+/// An input source that can be opened again from a device node once it has gone away.
+pub trait Reconnect: InputEventSource + Sized {
+    fn identity(&self) -> DeviceIdentity;
+
+    /// Opens and grabs the device node at `path`, carrying over any settings of `self` such as
+    /// its device id.
+    fn reopen(&self, path: &Path) -> Result<Self>;
+}
+
+/// Watches a directory for device nodes being added. Tests point it at a scratch directory
+/// holding fake device nodes.
+pub struct DirectoryWatcher {
+    dir: PathBuf,
+    inotify: Inotify,
+    buffer: [u8; 4096],
+    cancel: Cancel,
+}
+
+impl DirectoryWatcher {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<DirectoryWatcher> {
+        let inotify = Inotify::init()?;
+        // udev fixes up the permissions of new nodes after creating them, so opening a node may
+        // only succeed once its attributes have changed
+        inotify.watches().add(dir.as_ref(), WatchMask::CREATE | WatchMask::ATTRIB)?;
+        Ok(DirectoryWatcher {
+            dir: dir.as_ref().to_path_buf(),
+            inotify,
+            buffer: [0; 4096],
+            cancel: Cancel::default(),
+        })
+    }
+
+    /// Sets what stops a blocked `wait`, which then fails with `Error::Cancelled`.
+    pub fn with_cancel(mut self, cancel: Cancel) -> DirectoryWatcher {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Lists the device nodes currently in the directory.
+    pub fn existing(&self) -> Result<Vec<PathBuf>> {
+        event_nodes(&self.dir)
+    }
+
+    /// Blocks until device nodes are added to or changed in the directory and returns them.
+    pub fn wait(&mut self) -> Result<Vec<PathBuf>> {
+        loop {
+            let events = match self.inotify.read_events(&mut self.buffer) {
+                Ok(events) => events,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.cancel.wait(self.inotify.as_raw_fd())?;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let paths: Vec<PathBuf> = events
+                .filter_map(|event| event.name.map(|name| self.dir.join(name)))
+                .filter(|path| is_event_node(path))
+                .collect();
+            if !paths.is_empty() {
+                return Ok(paths);
+            }
+        }
+    }
+}
+
+/// Lists the device nodes in `dir` in order.
+pub fn event_nodes<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_event_node(path))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn is_event_node(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("event"))
+}
+
+/// Reconnecting wraps a grabbed device and, when it is unplugged, waits for a device with the
+/// same identity to show up again and grabs that instead. Keys held when the device went away are
+/// released first so that nothing stays stuck while it is gone. Since only the input side is
+/// replaced, the virtual output device stays the same throughout.
+pub struct Reconnecting<S: Reconnect> {
+    device: S,
+    identity: DeviceIdentity,
+    connected: bool,
+    watcher: DirectoryWatcher,
+    held: HashMap<KeyCode, InputEvent>,
+    pending: VecDeque<InputEvent>,
+}
+
+impl<S: Reconnect> Reconnecting<S> {
+    pub fn new(device: S, watcher: DirectoryWatcher) -> Self {
+        Reconnecting {
+            identity: device.identity(),
+            device,
+            connected: true,
+            watcher,
+            held: HashMap::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn track(&mut self, e: &InputEvent) {
+        if let EventCode::KeyCode(kc) = e.code {
+            match e.state {
+                KeyState::Down | KeyState::Held => self.held.insert(kc, *e),
+                _ => self.held.remove(&kc),
+            };
+        }
+    }
+
+    fn disconnected(&mut self) {
+        warn!("device {:?} went away, waiting for it to return", self.identity.name);
+        self.connected = false;
+        let now = SystemTime::now();
+        for (_, down) in self.held.drain() {
+            self.pending.push_back(InputEvent {
+                time: now,
+                state: KeyState::Up,
+                value: KeyState::Up as i32,
+                ..down
+            });
+        }
+        if let Some(last) = self.pending.back() {
+            let report = InputEvent {
+                code: EventCode::SynCode(SynCode::Report),
+                value: 0,
+                ..*last
+            };
+            self.pending.push_back(report);
+        }
+    }
+
+    // Tries to grab each of the given device nodes, returning true once the device is back.
+    fn reconnect(&mut self, paths: Vec<PathBuf>) -> bool {
+        for path in paths {
+            let device = match self.device.reopen(&path) {
+                Ok(device) => device,
+                Err(e) => {
+                    debug!("unable to open {}: {:?}", path.display(), e);
+                    continue;
+                }
+            };
+            if device.identity() == self.identity {
+                info!("device {:?} returned as {}", self.identity.name, path.display());
+                self.device = device;
+                self.connected = true;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<S: Reconnect> InputEventSource for Reconnecting<S> {
+    fn recv(&mut self) -> Result<InputEvent> {
+        loop {
+            if let Some(e) = self.pending.pop_front() {
+                return Ok(e);
+            }
+            if !self.connected {
+                // the device may have returned before the watcher was consulted
+                let existing = self.watcher.existing()?;
+                if !self.reconnect(existing) {
+                    while !self.connected {
+                        let added = self.watcher.wait()?;
+                        self.reconnect(added);
+                    }
+                }
+                continue;
+            }
+            match self.device.recv() {
+                Ok(e) => {
+                    self.track(&e);
+                    return Ok(e);
+                }
+                Err(e) if e.is_disconnected() => self.disconnected(),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+        self.device.pressed_keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io;
+    use std::thread;
+    use std::time::Duration;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+    use num::FromPrimitive;
+
+    use super::*;
+    use crate::errors::Error;
+    use crate::events::KeyCode::*;
+
+    // A fake device node is a file holding the device's name and physical path followed by the
+    // key codes it reports as pressed before being unplugged.
+    struct FakeDevice {
+        identity: DeviceIdentity,
+        keys: VecDeque<KeyCode>,
+    }
+
+    impl FakeDevice {
+        fn open(path: &Path) -> Result<FakeDevice> {
+            let contents = fs::read_to_string(path)?;
+            let mut lines = contents.lines();
+            Ok(FakeDevice {
+                identity: DeviceIdentity {
+                    name: lines.next().map(String::from),
+                    phys: lines.next().map(String::from),
+                },
+                keys: lines
+                    .filter_map(|line| line.parse::<u16>().ok())
+                    .filter_map(KeyCode::from_u16)
+                    .collect(),
+            })
+        }
+    }
+
+    impl InputEventSource for FakeDevice {
+        fn recv(&mut self) -> Result<InputEvent> {
+            match self.keys.pop_front() {
+                Some(kc) => Ok(key(kc, KeyState::Down)),
+                // ENODEV, as reported by evdev once a device has been unplugged
+                None => Err(Error::IO(io::Error::from_raw_os_error(19))),
+            }
+        }
+    }
+
+    impl Reconnect for FakeDevice {
+        fn identity(&self) -> DeviceIdentity {
+            self.identity.clone()
+        }
+
+        fn reopen(&self, path: &Path) -> Result<FakeDevice> {
+            FakeDevice::open(path)
+        }
+    }
+
+    fn key(kc: KeyCode, state: KeyState) -> InputEvent {
+        InputEvent {
+            time: SystemTime::UNIX_EPOCH,
+            code: EventCode::KeyCode(kc),
+            state,
+            value: state as i32,
+            device: 0,
+        }
+    }
+
+    #[test]
+    fn reconnects_to_device_with_same_identity() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("qsk-hotplug-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+
+        fs::write(dir.join("event0"), "keyboard\nusb-1/input0\n30\n")?;
+        let device = FakeDevice::open(&dir.join("event0"))?;
+        let mut reconnecting = Reconnecting::new(device, DirectoryWatcher::new(&dir)?);
+
+        // unplug the keyboard and plug in a mouse followed by the keyboard again
+        fs::remove_file(dir.join("event0"))?;
+        fs::write(dir.join("event1"), "mouse\nusb-2/input0\n272\n")?;
+        fs::write(dir.join("event2"), "keyboard\nusb-1/input0\n48\n")?;
+
+        let mut received = Vec::new();
+        for _ in 0..4 {
+            let e = reconnecting.recv()?;
+            received.push((e.code, e.state));
+        }
+
+        // unplug it again, this time plugging it back in while waiting for it. the node is linked
+        // into place so that it never appears without contents.
+        fs::remove_file(dir.join("event2"))?;
+        let staged = dir.with_extension("event3");
+        fs::write(&staged, "keyboard\nusb-1/input0\n46\n")?;
+        let plug = {
+            let (staged, node) = (staged.clone(), dir.join("event3"));
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                fs::hard_link(staged, node)
+            })
+        };
+        for _ in 0..3 {
+            let e = reconnecting.recv()?;
+            received.push((e.code, e.state));
+        }
+        plug.join().unwrap()?;
+        fs::remove_file(&staged)?;
+        fs::remove_dir_all(&dir)?;
+
+        assert_that!(
+            &received,
+            eq(vec![
+                (EventCode::KeyCode(KC_A), KeyState::Down),
+                (EventCode::KeyCode(KC_A), KeyState::Up),
+                (EventCode::SynCode(SynCode::Report), KeyState::Up),
+                (EventCode::KeyCode(KC_B), KeyState::Down),
+                (EventCode::KeyCode(KC_B), KeyState::Up),
+                (EventCode::SynCode(SynCode::Report), KeyState::Up),
+                (EventCode::KeyCode(KC_C), KeyState::Down),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn waiting_for_devices_can_be_cancelled() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("qsk-hotplug-cancel-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let cancel = Cancel::new()?;
+        let mut watcher = DirectoryWatcher::new(&dir)?.with_cancel(cancel.clone());
+
+        let waiting = thread::spawn(move || watcher.wait().map(|_| ()));
+        cancel.cancel();
+        let result = waiting.join().unwrap();
+        fs::remove_dir_all(&dir)?;
+
+        match result {
+            Err(Error::Cancelled) => Ok(()),
+            other => panic!("expected the wait to be cancelled, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use num::FromPrimitive;
+use serde::Serialize;
+
+use crate::events::{KeyCode, LedCode, EV_ABS, EV_KEY, EV_LED, EV_MSC, EV_REL, EV_SYN};
+
+/// By default `list-devices` only shows devices with more keys than this, which keyboards have,
+/// or with relative axes, which mice and trackballs have.
+pub const DEFAULT_MIN_KEYS: usize = 100;
+
+/// What `list-devices` reports about a device.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DeviceInfo {
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub phys: Option<String>,
+    pub vendor: u16,
+    pub product: u16,
+    pub version: u16,
+    pub key_count: usize,
+    pub relative_axes: bool,
+}
+
+impl DeviceInfo {
+    /// Returns true if the device should be listed without `--all`.
+    pub fn is_listed(&self, min_keys: usize) -> bool {
+        self.key_count > min_keys || self.relative_axes
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name.as_deref().unwrap_or("unknown"))?;
+        writeln!(f, "  key_count: {}", self.key_count)?;
+        writeln!(f, "  physical path: {}", self.phys.as_deref().unwrap_or("unknown"))?;
+        writeln!(f, "  id: {:04x}:{:04x}", self.vendor, self.product)?;
+        writeln!(f, "  system path: {}", self.path.display())
+    }
+}
+
+/// What `inspect-device` reports about a device. Keys and LEDs are named after their `KeyCode`
+/// and `LedCode`; codes qsk has no name for are given as numbers.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DeviceInspection {
+    #[serde(flatten)]
+    pub info: DeviceInfo,
+    pub event_types: Vec<String>,
+    pub keys: Vec<String>,
+    pub leds: Vec<String>,
+    pub pressed_keys: Vec<String>,
+    pub lit_leds: Vec<String>,
+}
+
+impl fmt::Display for DeviceInspection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.info)?;
+        writeln!(f, "  version: {:04x}", self.info.version)?;
+        writeln!(f, "  event types: {}", self.event_types.join(" "))?;
+        writeln!(f, "  keys: {}", self.keys.join(" "))?;
+        writeln!(f, "  leds: {}", self.leds.join(" "))?;
+        writeln!(f, "  pressed keys: {}", self.pressed_keys.join(" "))?;
+        writeln!(f, "  lit leds: {}", self.lit_leds.join(" "))
+    }
+}
+
+pub fn event_type_name(ty: u16) -> String {
+    match ty {
+        EV_SYN => "EV_SYN".to_string(),
+        EV_KEY => "EV_KEY".to_string(),
+        EV_REL => "EV_REL".to_string(),
+        EV_ABS => "EV_ABS".to_string(),
+        EV_MSC => "EV_MSC".to_string(),
+        EV_LED => "EV_LED".to_string(),
+        _ => format!("{:#04x}", ty),
+    }
+}
+
+pub fn key_name(code: u16) -> String {
+    match KeyCode::from_u16(code) {
+        Some(kc) => kc.to_string(),
+        None => code.to_string(),
+    }
+}
+
+pub fn led_name(code: u16) -> String {
+    match LedCode::from_u16(code) {
+        Some(led) => format!("{:?}", led),
+        None => code.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    fn macro_pad() -> DeviceInfo {
+        DeviceInfo {
+            path: PathBuf::from("/dev/input/event12"),
+            name: Some("Macro Pad".to_string()),
+            phys: Some("usb-0000:00:14.0-2/input0".to_string()),
+            vendor: 0x1209,
+            product: 0x0001,
+            version: 0x0111,
+            key_count: 12,
+            relative_axes: false,
+        }
+    }
+
+    #[test]
+    fn macro_pads_need_a_lower_threshold() {
+        let info = macro_pad();
+        assert_that!(&info.is_listed(DEFAULT_MIN_KEYS), eq(false));
+        assert_that!(&info.is_listed(8), eq(true));
+    }
+
+    #[test]
+    fn render_inspection() -> serde_json::Result<()> {
+        let inspection = DeviceInspection {
+            info: macro_pad(),
+            event_types: vec![EV_SYN, EV_KEY, EV_LED].into_iter().map(event_type_name).collect(),
+            keys: vec![30, 48, 600].into_iter().map(key_name).collect(),
+            leds: vec![0, 1].into_iter().map(led_name).collect(),
+            pressed_keys: vec![key_name(30)],
+            lit_leds: vec![led_name(1)],
+        };
+
+        let rendered = inspection.to_string();
+        assert_that!(
+            &rendered.as_str(),
+            eq("Macro Pad
+  key_count: 12
+  physical path: usb-0000:00:14.0-2/input0
+  id: 1209:0001
+  system path: /dev/input/event12
+  version: 0111
+  event types: EV_SYN EV_KEY EV_LED
+  keys: KC_A KC_B 600
+  leds: NumLock CapsLock
+  pressed keys: KC_A
+  lit leds: CapsLock
+")
+        );
+
+        let json: serde_json::Value = serde_json::to_value(&inspection)?;
+        assert_that!(&json["name"], eq(serde_json::json!("Macro Pad")));
+        assert_that!(&json["vendor"], eq(serde_json::json!(0x1209)));
+        assert_that!(&json["keys"], eq(serde_json::json!(["KC_A", "KC_B", "600"])));
+        Ok(())
+    }
+}
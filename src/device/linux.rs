@@ -1,183 +1,214 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::fs::File;
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
 
 use evdev_rs;
 use evdev_rs::enums;
+use evdev_rs::util;
+use evdev_rs::DeviceWrapper;
 use evdev_rs::GrabMode;
 use evdev_rs::TimeVal;
-use log::error;
 
-use crate::errors::Error;
-use crate::errors::Result;
-
-use crate::events;
-use crate::events::{EventCode, KeyCode, KeyState, SynCode};
-use crate::device::traits::{InputEventSink, InputEventSource};
+use crate::device::backend::{Backend, Capabilities, Output, OutputConfig};
+use crate::device::cancel::Cancel;
+use crate::device::gamepad::{self, AxisRange};
+use crate::device::hotplug::{self, DeviceIdentity};
+use crate::device::info::{self, DeviceInfo, DeviceInspection};
+use crate::device::raw::RawEvent;
+use crate::errors::{Error, Result};
+use crate::events::{self, AbsoluteCode};
+
+// The highest key and LED codes, KEY_MAX and LED_MAX in linux/input-event-codes.h.
+const KEY_MAX: u16 = 0x2ff;
+const LED_MAX: u16 = 0x0f;
+
+/// The backend built on libevdev through evdev-rs.
+pub struct EvdevRs {
+    inner: evdev_rs::Device,
+    fd: RawFd,
+    path: PathBuf,
+}
 
-pub struct InputEvent(events::InputEvent);
+unsafe impl Send for EvdevRs {}
 
-pub struct Device {
-    inner: Arc<Mutex<evdev_rs::Device>>,
+fn open(path: &Path) -> Result<evdev_rs::Device> {
+    Ok(evdev_rs::Device::new_from_file(File::open(path)?)?)
 }
 
-unsafe impl Send for Device {}
+fn has_code(dev: &evdev_rs::Device, ty: u16, code: u16) -> bool {
+    dev.has_event_code(&util::int_to_event_code(ty as u32, code as u32))
+}
 
-impl TryFrom<evdev_rs::InputEvent> for InputEvent {
-    type Error = Error;
+fn codes(dev: &evdev_rs::Device, ty: u16, max: u16) -> Vec<u16> {
+    (0..=max).filter(|code| has_code(dev, ty, *code)).collect()
+}
 
-    fn try_from(ev: evdev_rs::InputEvent) -> Result<InputEvent> {
-        let c = match ev.event_code {
-            enums::EventCode::EV_KEY(ref ec) => {
-                let kc: Option<KeyCode> = num::FromPrimitive::from_u16(ec.clone() as u16);
-                match kc {
-                    Some(code) => Some(EventCode::KeyCode(code)),
-                    None => None,
-                }
-            }
-            enums::EventCode::EV_SYN(ref ec) => {
-                let sc: Option<SynCode> = num::FromPrimitive::from_u16(ec.clone() as u16);
-                match sc {
-                    Some(code) => Some(EventCode::SynCode(code)),
-                    None => None,
-                }
-            }
-            _ => None,
-        };
-        match c {
-            Some(code) => Ok(InputEvent(events::InputEvent {
-                time: UNIX_EPOCH
-                    + Duration::new(ev.time.tv_sec as u64, ev.time.tv_usec as u32 * 1000 as u32),
-                code,
-                state: i32_into_ks(ev.value),
-            })),
-            None => Err(Error::UnrecognizedEvdevRSInputEvent { e: ev }),
-        }
-    }
+fn active_codes(dev: &evdev_rs::Device, ty: u16, max: u16) -> Vec<u16> {
+    (0..=max)
+        .filter(|code| dev.event_value(&util::int_to_event_code(ty as u32, *code as u32)) == Some(1))
+        .collect()
 }
 
-impl TryFrom<InputEvent> for evdev_rs::InputEvent {
-    type Error = Error;
-
-    fn try_from(ie: InputEvent) -> Result<evdev_rs::InputEvent> {
-        let c = match ie.0.code {
-            EventCode::KeyCode(c) => match enums::int_to_ev_key(c as u32) {
-                Some(key) => Some(enums::EventCode::EV_KEY(key)),
-                None => None,
-            },
-            EventCode::SynCode(c) => match enums::int_to_ev_syn(c as u32) {
-                Some(key) => Some(enums::EventCode::EV_SYN(key)),
-                None => None,
-            },
-        };
-
-        let d = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        match c {
-            Some(event_code) => Ok(evdev_rs::InputEvent {
-                time: TimeVal {
-                    tv_sec: d.as_secs() as i64,
-                    tv_usec: d.subsec_micros() as i64,
-                },
-                event_code,
-                value: ie.0.state as i32,
-            }),
-            None => Err(Error::UnrecognizedInputEvent { e: ie.0 }),
-        }
+fn device_info(path: PathBuf, dev: &evdev_rs::Device) -> DeviceInfo {
+    DeviceInfo {
+        path,
+        name: dev.name().map(String::from),
+        phys: dev.phys().map(String::from),
+        vendor: dev.vendor_id(),
+        product: dev.product_id(),
+        version: dev.version(),
+        key_count: codes(dev, events::EV_KEY, KEY_MAX).len(),
+        relative_axes: dev.has_event_type(&enums::EventType::EV_REL),
     }
 }
 
-impl Device {
-    pub fn from_path(path: PathBuf) -> Result<Device> {
-        let f = File::open(path)?;
-        let mut d = evdev_rs::Device::new_from_file(f).unwrap();
+impl Backend for EvdevRs {
+    type Output = EvdevRsOutput;
+
+    // libevdev reads from the file whenever its own queue is empty, so the file is opened
+    // non-blocking and waited on separately
+    fn open(path: &Path) -> Result<EvdevRs> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+        let fd = file.as_raw_fd();
+        let mut d = evdev_rs::Device::new_from_file(file)?;
         d.grab(GrabMode::Grab)?;
-        Ok(Device {
-            inner: Arc::new(Mutex::new(d)),
+        Ok(EvdevRs {
+            inner: d,
+            fd,
+            path: path.to_path_buf(),
         })
     }
 
-    pub fn new_uinput_device(&self) -> Result<UInputDevice> {
-        let guard = match self.inner.lock() {
-            Ok(a) => a,
-            Err(p_err) => {
-                let g = p_err.into_inner();
-                error!("recovered Device");
-                g
-            }
-        };
-        let d = evdev_rs::UInputDevice::create_from_device(&*guard)?;
-        Ok(UInputDevice {
-            inner: Arc::new(Mutex::new(d)),
+    fn read(&mut self) -> Result<RawEvent> {
+        let (_, ev) = self.inner.next_event(evdev_rs::ReadFlag::NORMAL)?;
+        let (ty, code) = util::event_code_to_int(&ev.event_code);
+        Ok(RawEvent {
+            time: UNIX_EPOCH + Duration::new(ev.time.tv_sec as u64, ev.time.tv_usec as u32 * 1000),
+            ty: ty as u16,
+            code: code as u16,
+            value: ev.value,
         })
     }
-}
 
-impl InputEventSource for Device {
-    fn recv(&mut self) -> Result<events::InputEvent> {
-        let guard = match self.inner.lock() {
-            Ok(a) => a,
-            Err(p_err) => {
-                let g = p_err.into_inner();
-                error!("recovered Device");
-                g
-            }
-        };
-        match guard.next_event(evdev_rs::ReadFlag::NORMAL | evdev_rs::ReadFlag::BLOCKING) {
-            Ok(ev) => match InputEvent::try_from(ev.1) {
-                Ok(ie) => Ok(ie.0),
-                Err(e) => Err(e),
-            },
-            Err(e) => Err(Error::IO(e)),
+    fn wait(&self, cancel: &Cancel) -> Result<()> {
+        cancel.wait(self.fd)
+    }
+
+    fn identity(&self) -> DeviceIdentity {
+        DeviceIdentity {
+            name: self.inner.name().map(String::from),
+            phys: self.inner.phys().map(String::from),
         }
     }
-}
 
-pub struct UInputDevice {
-    inner: Arc<Mutex<evdev_rs::UInputDevice>>,
-}
+    fn axis_ranges(&self) -> HashMap<AbsoluteCode, AxisRange> {
+        gamepad::axes()
+            .filter_map(|axis| {
+                let code = util::int_to_event_code(events::EV_ABS as u32, axis as u32);
+                self.inner
+                    .abs_info(&code)
+                    .map(|info| (axis, AxisRange::new(info.minimum, info.maximum, info.value)))
+            })
+            .collect()
+    }
 
-unsafe impl Send for UInputDevice {}
+    fn keys(&self) -> Vec<u16> {
+        codes(&self.inner, events::EV_KEY, KEY_MAX)
+    }
 
-impl InputEventSink for UInputDevice {
-    fn send(&mut self, e: events::InputEvent) -> Result<()> {
-        let guard = match self.inner.lock() {
-            Ok(a) => a,
-            Err(p_err) => {
-                let g = p_err.into_inner();
-                error!("recovered Device");
-                g
+    // libevdev only updates its view of the device as events are read, but a new handle starts
+    // out with the kernel's current state
+    fn pressed_keys(&self) -> Result<Vec<u16>> {
+        Ok(active_codes(&open(&self.path)?, events::EV_KEY, KEY_MAX))
+    }
+
+    // libevdev copies everything a device supports, including LEDs, onto the virtual device. The
+    // copy is made from a second handle on the source device whose keys are adjusted to match the
+    // capabilities; libevdev drops events for disabled codes, so the grabbed handle is left alone.
+    fn create_output(&self, config: &OutputConfig, capabilities: &Capabilities) -> Result<EvdevRsOutput> {
+        let template = open(&self.path)?;
+        let key = |code: u16| util::int_to_event_code(events::EV_KEY as u32, code as u32);
+        for code in self.keys() {
+            if !capabilities.keys.contains(&code) {
+                template.disable(&key(code))?;
             }
-        };
-
-        let ie = evdev_rs::InputEvent::try_from(InputEvent(e))?;
-        match guard.write_event(&ie) {
-            Ok(_) => (),
-            Err(e) => return Err(Error::IO(e)),
-        };
-        let t: TimeVal;
-        match TimeVal::try_from(e.time) {
-            Ok(tv) => t = tv,
-            Err(e) => return Err(Error::SystemTimeError(e)),
         }
-        match guard.write_event(&evdev_rs::InputEvent {
-            time: t,
-            event_code: enums::EventCode::EV_SYN(enums::EV_SYN::SYN_REPORT),
-            value: 0,
-        }) {
-            Ok(_) => return Ok(()),
-            Err(e) => return Err(Error::IO(e)),
+        for code in capabilities.keys.iter() {
+            template.enable(&key(*code))?;
+        }
+        for axis in capabilities.relative_axes.iter() {
+            template.enable(&util::int_to_event_code(events::EV_REL as u32, *axis as u32))?;
+        }
+        if let Some(name) = &config.name {
+            template.set_name(name);
+        }
+        if let Some(bustype) = config.bustype {
+            template.set_bustype(bustype);
         }
+        if let Some(vendor) = config.vendor {
+            template.set_vendor_id(vendor);
+        }
+        if let Some(product) = config.product {
+            template.set_product_id(product);
+        }
+        Ok(EvdevRsOutput {
+            inner: evdev_rs::UInputDevice::create_from_device(&template)?,
+        })
+    }
+
+    fn enumerate() -> Vec<DeviceInfo> {
+        hotplug::event_nodes(hotplug::INPUT_DIR)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|path| open(&path).ok().map(|dev| device_info(path, &dev)))
+            .collect()
+    }
+
+    fn inspect(path: &Path) -> Result<DeviceInspection> {
+        let dev = open(path)?;
+        Ok(DeviceInspection {
+            event_types: enums::EventType::EV_SYN
+                .iter()
+                .filter(|ty| dev.has_event_type(ty))
+                .map(|ty| info::event_type_name(ty as u16))
+                .collect(),
+            keys: codes(&dev, events::EV_KEY, KEY_MAX).into_iter().map(info::key_name).collect(),
+            leds: codes(&dev, events::EV_LED, LED_MAX).into_iter().map(info::led_name).collect(),
+            pressed_keys: active_codes(&dev, events::EV_KEY, KEY_MAX)
+                .into_iter()
+                .map(info::key_name)
+                .collect(),
+            lit_leds: active_codes(&dev, events::EV_LED, LED_MAX)
+                .into_iter()
+                .map(info::led_name)
+                .collect(),
+            info: device_info(path.to_path_buf(), &dev),
+        })
     }
 }
 
-fn i32_into_ks(i: i32) -> KeyState {
-    match i {
-        0 => KeyState::Up,
-        1 => KeyState::Down,
-        2 => KeyState::Held,
-        _ => KeyState::NotImplemented,
+pub struct EvdevRsOutput {
+    inner: evdev_rs::UInputDevice,
+}
+
+unsafe impl Send for EvdevRsOutput {}
+
+impl Output for EvdevRsOutput {
+    fn write(&mut self, events: &[RawEvent]) -> Result<()> {
+        for e in events {
+            self.inner.write_event(&evdev_rs::InputEvent {
+                time: TimeVal::try_from(e.time).map_err(Error::SystemTimeError)?,
+                event_code: util::int_to_event_code(e.ty as u32, e.code as u32),
+                value: e.value,
+            })?;
+        }
+        Ok(())
     }
 }
@@ -1,141 +1,226 @@
-use futures::executor::block_on;
-use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 
 use evdev;
 use evdev::uinput;
-
+use log::warn;
+
+use crate::device::backend::{Backend, Capabilities, Output, OutputConfig};
+use crate::device::cancel::Cancel;
+use crate::device::gamepad::{self, AxisRange};
+use crate::device::hotplug::DeviceIdentity;
+use crate::device::info::{self, DeviceInfo, DeviceInspection};
+use crate::device::raw::RawEvent;
 use crate::errors::{Error, Result};
-use crate::events;
-use crate::events::{EventCode, KeyCode, KeyState, SynCode};
-use crate::device::traits::{InputEventSink, InputEventSource};
+use crate::events::AbsoluteCode;
 
-pub struct InputEvent(events::InputEvent);
+/// The backend built on the evdev crate, which talks to the kernel directly.
+pub struct Evdev {
+    inner: evdev::Device,
+    pending: VecDeque<RawEvent>,
+}
 
-pub struct Device {
-    inner: evdev::EventStream,
+fn set_nonblocking(dev: &evdev::Device) -> Result<()> {
+    let fd = dev.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags == -1 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } == -1 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
 }
 
-impl Device {
-    pub fn from_path(path: PathBuf) -> Result<Device> {
-        let mut d = evdev::Device::open(&path)?;
-        d.grab()?;
-        Ok(Device {
-            inner: d.into_event_stream()?,
-        })
+fn device_info(path: PathBuf, dev: &evdev::Device) -> DeviceInfo {
+    DeviceInfo {
+        path,
+        name: dev.name().map(String::from),
+        phys: dev.physical_path().map(String::from),
+        vendor: dev.input_id().vendor(),
+        product: dev.input_id().product(),
+        version: dev.input_id().version(),
+        key_count: dev.supported_keys().map_or(0, |keys| keys.iter().count()),
+        relative_axes: dev.supported_relative_axes().is_some(),
     }
+}
 
-    pub fn from_evdev_device(mut d: evdev::Device) -> Result<Device> {
+impl Backend for Evdev {
+    type Output = EvdevOutput;
+
+    fn open(path: &Path) -> Result<Evdev> {
+        let mut d = evdev::Device::open(path)?;
         d.grab()?;
-        Ok(Device {
-            inner: d.into_event_stream()?,
+        set_nonblocking(&d)?;
+        Ok(Evdev {
+            inner: d,
+            pending: VecDeque::new(),
         })
     }
 
-    pub fn new_uinput_device(&self, name: String) -> Result<UInputDevice> {
-        let mut vdb = uinput::VirtualDeviceBuilder::new()?;
-        vdb = vdb.name(&name);
-        if let Some(sks) = self.inner.device().supported_keys() {
-            vdb = vdb.with_keys(sks)?;
-        } else {
-            return Err(Error::NoSupportedKeys);
+    // fetch_events returns everything the kernel has buffered, which is handed out one at a time
+    fn read(&mut self) -> Result<RawEvent> {
+        if self.pending.is_empty() {
+            self.pending.extend(self.inner.fetch_events()?.map(|ev| RawEvent {
+                time: ev.timestamp(),
+                ty: ev.event_type().0,
+                code: ev.code(),
+                value: ev.value(),
+            }));
         }
-        Ok(UInputDevice {
-            inner: vdb.build()?,
-        })
+        self.pending
+            .pop_front()
+            .ok_or_else(|| Error::IO(io::ErrorKind::WouldBlock.into()))
     }
 
-    pub fn list() -> Result<()> {
-        for (path, dev) in evdev::enumerate() {
-            if let Some(keys) = dev.supported_keys() {
-                let mut key_count = 0;
-                for _key in keys.iter() {
-                    //println!("  key: {:?}", key);
-                    key_count += 1;
-                }
-                if key_count > 100 {
-                    println!("{}", dev.name().unwrap_or("unknown"));
-                    println!("  key_count: {}", key_count);
-                    println!(
-                        "  physical path: {}",
-                        dev.physical_path().unwrap_or("unknown")
-                    );
-                    println!("  system path: {}", path.display());
-                }
-            }
+    fn wait(&self, cancel: &Cancel) -> Result<()> {
+        cancel.wait(self.inner.as_raw_fd())
+    }
+
+    fn identity(&self) -> DeviceIdentity {
+        let dev = &self.inner;
+        DeviceIdentity {
+            name: dev.name().map(String::from),
+            phys: dev.physical_path().map(String::from),
         }
-        Ok(())
     }
-}
 
-impl TryFrom<evdev::InputEvent> for InputEvent {
-    type Error = Error;
-
-    fn try_from(ev: evdev::InputEvent) -> Result<InputEvent> {
-        let ec = match ev.event_type() {
-            evdev::EventType::KEY => {
-                let kc: Option<KeyCode> = num::FromPrimitive::from_u16(ev.code() as u16);
-                match kc {
-                    Some(code) => Some(EventCode::KeyCode(code)),
-                    None => None,
-                }
-            }
-            evdev::EventType::SYNCHRONIZATION => {
-                let kc: Option<SynCode> = num::FromPrimitive::from_u16(ev.code() as u16);
-                match kc {
-                    Some(code) => Some(EventCode::SynCode(code)),
-                    None => None,
-                }
-            }
-            _ => None,
+    fn axis_ranges(&self) -> HashMap<AbsoluteCode, AxisRange> {
+        let dev = &self.inner;
+        let (supported, abs_state) = match (dev.supported_absolute_axes(), dev.get_abs_state()) {
+            (Some(supported), Ok(abs_state)) => (supported, abs_state),
+            _ => return HashMap::new(),
         };
-        match ec {
-            Some(code) => Ok(InputEvent( events::InputEvent {
-                time: ev.timestamp(),
-                code,
-                state: i32_into_ks(ev.value()),
-            })),
-            None => Err(Error::UnrecognizedEvdevInputEvent { e: ev }),
-        }
+        gamepad::axes()
+            .filter(|axis| supported.contains(evdev::AbsoluteAxisType(*axis as u16)))
+            .map(|axis| {
+                let info = abs_state[axis as usize];
+                (axis, AxisRange::new(info.minimum, info.maximum, info.value))
+            })
+            .collect()
     }
-}
 
-impl TryFrom<InputEvent> for evdev::InputEvent {
-    type Error = Error;
+    fn keys(&self) -> Vec<u16> {
+        self.inner
+            .supported_keys()
+            .map_or_else(Vec::new, |keys| keys.iter().map(|key| key.code()).collect())
+    }
 
-    fn try_from(ie: InputEvent) -> Result<evdev::InputEvent> {
-        let (ty, code) = match ie.0.code {
-            EventCode::KeyCode(c) => (evdev::EventType::SYNCHRONIZATION, c as i16),
-            EventCode::SynCode(c) => (evdev::EventType::KEY, c as i16),
-        };
-        Ok(evdev::InputEvent::new(ty, code as u16, ie.0.state as i32))
+    fn pressed_keys(&self) -> Result<Vec<u16>> {
+        let keys = self.inner.get_key_state()?;
+        Ok(keys.iter().map(|key| key.code()).collect())
     }
-}
 
-impl InputEventSource for Device {
-    fn recv(&mut self) -> Result<events::InputEvent> {
-        let ev = block_on(self.inner.next_event())?;
-        Ok(InputEvent::try_from(ev)?.0)
+    // The virtual device mirrors the relative axes, absolute axes and misc events of the source
+    // device so that events passed through unchanged can be emitted. The evdev crate's builder
+    // offers no way to declare LEDs, so the kernel discards LED events written to the device.
+    fn create_output(&self, config: &OutputConfig, capabilities: &Capabilities) -> Result<EvdevOutput> {
+        let dev = &self.inner;
+        let id = dev.input_id();
+        let mut vdb = uinput::VirtualDeviceBuilder::new()?;
+        vdb = vdb.name(config.name.as_deref().or_else(|| dev.name()).unwrap_or("qsk"));
+        vdb = vdb.input_id(evdev::InputId::new(
+            evdev::BusType(config.bustype.unwrap_or_else(|| id.bus_type().0)),
+            config.vendor.unwrap_or_else(|| id.vendor()),
+            config.product.unwrap_or_else(|| id.product()),
+            id.version(),
+        ));
+
+        if capabilities.keys.is_empty() {
+            return Err(Error::NoSupportedKeys);
+        }
+        let mut keys = evdev::AttributeSet::<evdev::Key>::new();
+        capabilities.keys.iter().for_each(|key| keys.insert(evdev::Key::new(*key)));
+        vdb = vdb.with_keys(&keys)?;
+
+        let mut relative_axes = evdev::AttributeSet::<evdev::RelativeAxisType>::new();
+        if let Some(axes) = dev.supported_relative_axes() {
+            axes.iter().for_each(|axis| relative_axes.insert(axis));
+        }
+        capabilities
+            .relative_axes
+            .iter()
+            .for_each(|axis| relative_axes.insert(evdev::RelativeAxisType(*axis)));
+        vdb = vdb.with_relative_axes(&relative_axes)?;
+
+        if let Some(axes) = dev.supported_absolute_axes() {
+            let abs_state = dev.get_abs_state()?;
+            for axis in axes.iter() {
+                let info = abs_state[axis.0 as usize];
+                vdb = vdb.with_absolute_axis(&evdev::UinputAbsSetup::new(
+                    axis,
+                    evdev::AbsInfo::new(
+                        info.value,
+                        info.minimum,
+                        info.maximum,
+                        info.fuzz,
+                        info.flat,
+                        info.resolution,
+                    ),
+                ))?;
+            }
+        }
+        if let Some(misc) = dev.misc_properties() {
+            vdb = vdb.with_msc(misc)?;
+        }
+        if dev.supported_leds().is_some_and(|leds| leds.iter().next().is_some()) {
+            warn!(
+                "the evdev backend can't give the virtual device LEDs, so LED events from {:?} are dropped",
+                dev.name()
+            );
+        }
+        Ok(EvdevOutput {
+            inner: vdb.build()?,
+        })
+    }
+
+    fn enumerate() -> Vec<DeviceInfo> {
+        evdev::enumerate()
+            .map(|(path, dev)| device_info(path, &dev))
+            .collect()
     }
-}
 
-fn i32_into_ks(i: i32) -> KeyState {
-    match i {
-        0 => KeyState::Up,
-        1 => KeyState::Down,
-        2 => KeyState::Held,
-        _ => KeyState::NotImplemented,
+    fn inspect(path: &Path) -> Result<DeviceInspection> {
+        let dev = evdev::Device::open(path)?;
+        let key_names = |keys: &evdev::AttributeSetRef<evdev::Key>| -> Vec<String> {
+            keys.iter().map(|key| info::key_name(key.code())).collect()
+        };
+        let led_names = |leds: &evdev::AttributeSetRef<evdev::LedType>| -> Vec<String> {
+            leds.iter().map(|led| info::led_name(led.0)).collect()
+        };
+        Ok(DeviceInspection {
+            event_types: dev
+                .supported_events()
+                .iter()
+                .map(|ty| info::event_type_name(ty.0))
+                .collect(),
+            keys: dev.supported_keys().map(key_names).unwrap_or_default(),
+            leds: dev.supported_leds().map(led_names).unwrap_or_default(),
+            pressed_keys: key_names(&dev.get_key_state()?),
+            lit_leds: led_names(&dev.get_led_state()?),
+            info: device_info(path.to_path_buf(), &dev),
+        })
     }
 }
 
-pub struct UInputDevice {
+pub struct EvdevOutput {
     inner: evdev::uinput::VirtualDevice,
 }
 
-impl InputEventSink for UInputDevice {
-    fn send(&mut self, ie: events::InputEvent) -> Result<()> {
-        let evdev_ie = evdev::InputEvent::try_from(InputEvent(ie))?;
-        self.inner.emit(&[evdev_ie])?;
+impl Output for EvdevOutput {
+    // emit follows the events it's given with a SYN_REPORT of its own, so each report is written
+    // as the events leading up to it
+    fn write(&mut self, events: &[RawEvent]) -> Result<()> {
+        for frame in events.split_inclusive(|e| e.is_syn_report()) {
+            let body = match frame.split_last() {
+                Some((last, body)) if last.is_syn_report() => body,
+                _ => frame,
+            };
+            let body: Vec<evdev::InputEvent> = body
+                .iter()
+                .map(|e| evdev::InputEvent::new(evdev::EventType(e.ty), e.code, e.value))
+                .collect();
+            self.inner.emit(&body)?;
+        }
         Ok(())
     }
 }
@@ -1,3 +1,27 @@
 pub mod traits;
+pub mod frames;
+pub mod backend;
+pub mod cancel;
+pub mod gamepad;
+pub mod hotplug;
+pub mod info;
+pub mod raw;
+pub mod reader;
+pub mod selector;
+#[cfg(feature = "backend-evdev-rs")]
 pub mod linux;
+#[cfg(feature = "backend-evdev")]
 pub mod linux_evdev;
+#[cfg(test)]
+mod conformance;
+#[cfg(test)]
+mod fake;
+
+#[cfg(not(any(feature = "backend-evdev-rs", feature = "backend-evdev")))]
+compile_error!("enable one of the backend-evdev-rs or backend-evdev features");
+
+/// The backend used when both are enabled is evdev-rs, the default.
+#[cfg(feature = "backend-evdev-rs")]
+pub type PlatformDevice = backend::Device<linux::EvdevRs>;
+#[cfg(all(feature = "backend-evdev", not(feature = "backend-evdev-rs")))]
+pub type PlatformDevice = backend::Device<linux_evdev::Evdev>;
@@ -0,0 +1,70 @@
+use std::time::SystemTime;
+
+use crate::events::{DeviceId, EventCode, InputEvent, KeyState, SynCode};
+
+/// An event as read from or written to a Linux input device. Backends only move these to and from
+/// the kernel; interpreting them is shared so that every backend behaves the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+    pub time: SystemTime,
+    pub ty: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl RawEvent {
+    pub fn decode(self, device: DeviceId) -> InputEvent {
+        InputEvent {
+            time: self.time,
+            code: EventCode::from_raw(self.ty, self.code),
+            state: key_state(self.value),
+            value: self.value,
+            device,
+        }
+    }
+
+    fn syn_report(time: SystemTime) -> RawEvent {
+        let (ty, code) = EventCode::SynCode(SynCode::Report).to_raw();
+        RawEvent {
+            time,
+            ty,
+            code,
+            value: 0,
+        }
+    }
+
+    pub fn is_syn_report(&self) -> bool {
+        EventCode::from_raw(self.ty, self.code) == EventCode::SynCode(SynCode::Report)
+    }
+}
+
+/// Encodes a frame for writing to a virtual device: its events followed by exactly one
+/// `SYN_REPORT`. Any `SYN_REPORT` among the events is dropped and an empty frame encodes to nothing.
+pub fn encode(frame: &[InputEvent]) -> Vec<RawEvent> {
+    let mut raw: Vec<RawEvent> = frame
+        .iter()
+        .map(|e| {
+            let (ty, code) = e.code.to_raw();
+            RawEvent {
+                time: e.time,
+                ty,
+                code,
+                value: e.raw_value(),
+            }
+        })
+        .filter(|e| !e.is_syn_report())
+        .collect();
+    if let Some(last) = raw.last() {
+        raw.push(RawEvent::syn_report(last.time));
+    }
+    raw
+}
+
+fn key_state(i: i32) -> KeyState {
+    match i {
+        0 => KeyState::Up,
+        1 => KeyState::Down,
+        2 => KeyState::Held,
+        _ => KeyState::NotImplemented,
+    }
+}
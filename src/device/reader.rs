@@ -0,0 +1,170 @@
+use std::sync::mpsc::Sender;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use log::{debug, error, trace};
+
+use crate::device::frames::{Frame, FrameReader};
+use crate::device::traits::InputEventSource;
+use crate::errors::{Error, Result};
+use crate::latency::Stamps;
+use crate::realtime::Realtime;
+
+// Errors other than a disconnect may be passing, so the reader tries again after a delay that
+// doubles with each error in a row, and gives up after this many.
+const READ_RETRIES: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Reader reads frames from an input source on an OS thread of its own and sends them to the
+/// engine, since reading from a device blocks and must not tie up the executor's threads. The
+/// thread ends once the source is cancelled or disconnected, keeps failing to read, or nobody
+/// receives its frames any more, and sends `Stopped` as its last message. Frames are stamped
+/// with when they were read, see `Stamps`. With `realtime` the thread runs with realtime
+/// priority.
+pub struct Reader {
+    thread: JoinHandle<()>,
+}
+
+/// The last message of a reader's thread, carrying the index of its device. Others may hold a
+/// sender to the same channel, so this is how the engine learns that no more frames will come.
+pub struct Stopped(pub usize);
+
+impl Reader {
+    pub fn spawn<S, T>(
+        device: usize,
+        source: S,
+        frames: Sender<T>,
+        realtime: Option<Realtime>,
+    ) -> Result<Reader>
+    where
+        S: InputEventSource + 'static,
+        T: From<(Frame, Stamps)> + From<Stopped> + Send + 'static,
+    {
+        let mut source = FrameReader::new(source);
+        let thread = thread::Builder::new()
+            .name(format!("reader-{}", device))
+            .spawn(move || {
+                if let Some(realtime) = &realtime {
+                    realtime.enter();
+                }
+                let mut failures = 0;
+                loop {
+                    match source.next_frame() {
+                        Ok(frame) => {
+                            failures = 0;
+                            trace!("received frame from device {}", device);
+                            let kernel = frame.first().map_or_else(SystemTime::now, |e| e.time);
+                            let frame = (frame, Stamps::read(kernel));
+                            // the channel is unbounded, so sending only fails once the
+                            // handler has stopped
+                            if frames.send(frame.into()).is_err() {
+                                debug!("channel closed, no longer reading from device {}", device);
+                                break;
+                            }
+                        }
+                        Err(Error::Cancelled) => {
+                            debug!("stopped reading from device {}", device);
+                            break;
+                        }
+                        Err(err) if err.is_disconnected() => {
+                            error!("device {} disconnected", device);
+                            break;
+                        }
+                        Err(err) if failures == READ_RETRIES => {
+                            error!("error reading from device {}, giving up: {:?}", device, err);
+                            break;
+                        }
+                        Err(err) => {
+                            error!("error reading from device {}: {:?}", device, err);
+                            thread::sleep(RETRY_DELAY * 2u32.pow(failures));
+                            failures += 1;
+                        }
+                    }
+                }
+                // fails if the handler has stopped already, which leaves nobody to tell
+                let _ = frames.send(Stopped(device).into());
+            })?;
+        Ok(Reader { thread })
+    }
+
+    /// Waits for the thread to end, which it does right after its source has been cancelled.
+    pub fn join(self) {
+        if self.thread.join().is_err() {
+            error!("reader thread panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc::channel;
+    use std::sync::Arc;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+    use crate::device::backend::Device;
+    use crate::device::cancel::Cancel;
+    use crate::device::conformance::Harness;
+    use crate::device::fake::FakeBackend;
+    use crate::device::raw::RawEvent;
+    use crate::engine::Input;
+    use crate::events::{EventCode, InputEvent, KeyCode, EV_KEY, EV_SYN};
+
+    fn raw(ty: u16, code: u16, value: i32) -> RawEvent {
+        RawEvent {
+            time: SystemTime::UNIX_EPOCH + Duration::from_millis(1),
+            ty,
+            code,
+            value,
+        }
+    }
+
+    #[test]
+    fn reading_stops_once_cancelled() -> Result<()> {
+        let path = FakeBackend::plug("keyboard", &[KeyCode::KC_A], &[raw(EV_KEY, 30, 1), raw(EV_SYN, 0, 0)]);
+        let cancel = Cancel::new()?;
+        let device = Device::<FakeBackend>::open(&path)?.with_cancel(cancel.clone());
+        let (sender, receiver) = channel::<Input>();
+        let reader = Reader::spawn(0, device, sender, None)?;
+
+        let frame = match receiver.recv().unwrap() {
+            Input::Frame(frame, _) => frame,
+            _ => panic!("expected a frame"),
+        };
+        assert_that!(&frame[0].code, eq(EventCode::KeyCode(KeyCode::KC_A)));
+
+        // the device has nothing more to report, so the reader is blocked until cancelled
+        cancel.cancel();
+        reader.join();
+        assert_that!(&matches!(receiver.recv(), Ok(Input::Stopped(0))), eq(true));
+        // the reader's sender was dropped with its thread
+        assert_that!(&receiver.recv().is_err(), eq(true));
+        Ok(())
+    }
+
+    // A source whose every read fails, counting how often it is read.
+    struct Failing(Arc<AtomicU32>);
+
+    impl InputEventSource for Failing {
+        fn recv(&mut self) -> Result<InputEvent> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(Error::NoEvents)
+        }
+    }
+
+    #[test]
+    fn reading_gives_up_after_repeated_errors() -> Result<()> {
+        let reads = Arc::new(AtomicU32::new(0));
+        let (sender, receiver) = channel::<Input>();
+        let reader = Reader::spawn(0, Failing(reads.clone()), sender, None)?;
+
+        reader.join();
+        assert_that!(&reads.load(Ordering::SeqCst), eq(READ_RETRIES + 1));
+        assert_that!(&matches!(receiver.recv(), Ok(Input::Stopped(0))), eq(true));
+        assert_that!(&receiver.recv().is_err(), eq(true));
+        Ok(())
+    }
+}
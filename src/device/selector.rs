@@ -0,0 +1,173 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::device::info::DeviceInfo;
+use crate::errors::{Error, Result};
+
+/// Identifies an input device by something more stable than its `/dev/input/eventN` path, which
+/// can change between boots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceSelector {
+    /// A device file, eg `/dev/input/event3` or a `/dev/input/by-id` symlink.
+    Path(PathBuf),
+    /// `name:<name>`, the device name, eg `name:AT Translated Set 2 keyboard`.
+    Name(String),
+    /// `id:<vendor>:<product>`, the hexadecimal USB vendor and product ids, eg `id:046d:c52b`.
+    Id { vendor: u16, product: u16 },
+    /// `phys:<path>`, the physical path, eg `phys:isa0060/serio0/input0`.
+    Phys(String),
+}
+
+impl FromStr for DeviceSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<DeviceSelector> {
+        if let Some(name) = s.strip_prefix("name:") {
+            return Ok(DeviceSelector::Name(name.to_string()));
+        }
+        if let Some(phys) = s.strip_prefix("phys:") {
+            return Ok(DeviceSelector::Phys(phys.to_string()));
+        }
+        if let Some(id) = s.strip_prefix("id:") {
+            let parse = |hex: &str| u16::from_str_radix(hex, 16).ok();
+            return match id.split_once(':') {
+                Some((vendor, product)) => match (parse(vendor), parse(product)) {
+                    (Some(vendor), Some(product)) => Ok(DeviceSelector::Id { vendor, product }),
+                    _ => Err(Error::InvalidDeviceSelector(s.to_string())),
+                },
+                None => Err(Error::InvalidDeviceSelector(s.to_string())),
+            };
+        }
+        Ok(DeviceSelector::Path(PathBuf::from(s)))
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeviceSelector::Path(path) => write!(f, "{}", path.display()),
+            DeviceSelector::Name(name) => write!(f, "name:{}", name),
+            DeviceSelector::Id { vendor, product } => write!(f, "id:{:04x}:{:04x}", vendor, product),
+            DeviceSelector::Phys(phys) => write!(f, "phys:{}", phys),
+        }
+    }
+}
+
+// Describes a device in the list of those matching an ambiguous selector.
+fn describe(info: &DeviceInfo) -> String {
+    format!(
+        "{} (name:{}, id:{:04x}:{:04x}, phys:{})",
+        info.path.display(),
+        info.name.as_deref().unwrap_or("unknown"),
+        info.vendor,
+        info.product,
+        info.phys.as_deref().unwrap_or("unknown"),
+    )
+}
+
+impl DeviceSelector {
+    fn matches(&self, candidate: &DeviceInfo) -> bool {
+        match self {
+            DeviceSelector::Path(path) => &candidate.path == path,
+            DeviceSelector::Name(name) => candidate.name.as_ref() == Some(name),
+            DeviceSelector::Id { vendor, product } => {
+                candidate.vendor == *vendor && candidate.product == *product
+            }
+            DeviceSelector::Phys(phys) => candidate.phys.as_ref() == Some(phys),
+        }
+    }
+
+    /// Returns the path of the one candidate matching the selector. Paths are returned as they
+    /// are, without consulting the candidates.
+    pub fn resolve(&self, candidates: &[DeviceInfo]) -> Result<PathBuf> {
+        if let DeviceSelector::Path(path) = self {
+            return Ok(path.clone());
+        }
+        let matching: Vec<&DeviceInfo> = candidates.iter().filter(|c| self.matches(c)).collect();
+        match matching[..] {
+            [candidate] => Ok(candidate.path.clone()),
+            [] => Err(Error::NoMatchingDevice(self.to_string())),
+            _ => Err(Error::AmbiguousDevice {
+                selector: self.to_string(),
+                candidates: matching.iter().map(|c| format!("  {}", describe(c))).collect::<Vec<_>>().join("\n"),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    fn candidate(path: &str, name: &str, phys: &str, vendor: u16, product: u16) -> DeviceInfo {
+        DeviceInfo {
+            path: PathBuf::from(path),
+            name: Some(name.to_string()),
+            phys: Some(phys.to_string()),
+            vendor,
+            product,
+            version: 1,
+            key_count: 0,
+            relative_axes: false,
+        }
+    }
+
+    #[test]
+    fn parse_selectors() -> Result<()> {
+        assert_that!(
+            &"name:AT Translated Set 2 keyboard".parse::<DeviceSelector>()?,
+            eq(DeviceSelector::Name("AT Translated Set 2 keyboard".to_string()))
+        );
+        assert_that!(
+            &"id:046d:c52b".parse::<DeviceSelector>()?,
+            eq(DeviceSelector::Id { vendor: 0x046d, product: 0xc52b })
+        );
+        assert_that!(
+            &"phys:isa0060/serio0/input0".parse::<DeviceSelector>()?,
+            eq(DeviceSelector::Phys("isa0060/serio0/input0".to_string()))
+        );
+        assert_that!(
+            &"/dev/input/event3".parse::<DeviceSelector>()?,
+            eq(DeviceSelector::Path(PathBuf::from("/dev/input/event3")))
+        );
+        assert_that!(&"id:046d".parse::<DeviceSelector>().is_err(), eq(true));
+        assert_that!(&"id:logi:c52b".parse::<DeviceSelector>().is_err(), eq(true));
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_selectors() -> Result<()> {
+        let candidates = vec![
+            candidate("/dev/input/event3", "AT Translated Set 2 keyboard", "isa0060/serio0/input0", 0x0001, 0x0001),
+            candidate("/dev/input/event7", "Logitech USB Receiver", "usb-0000:00:14.0-1/input0", 0x046d, 0xc52b),
+            candidate("/dev/input/event8", "Logitech USB Receiver Mouse", "usb-0000:00:14.0-1/input1", 0x046d, 0xc52b),
+        ];
+
+        let resolve = |s: &str| s.parse::<DeviceSelector>()?.resolve(&candidates);
+        assert_that!(
+            &resolve("name:AT Translated Set 2 keyboard")?,
+            eq(PathBuf::from("/dev/input/event3"))
+        );
+        assert_that!(
+            &resolve("phys:usb-0000:00:14.0-1/input1")?,
+            eq(PathBuf::from("/dev/input/event8"))
+        );
+        assert_that!(&resolve("/dev/input/event9")?, eq(PathBuf::from("/dev/input/event9")));
+
+        match resolve("id:046d:c52b") {
+            Err(Error::AmbiguousDevice { candidates, .. }) => {
+                assert_that!(&candidates.lines().count(), eq(2))
+            }
+            other => panic!("expected ambiguous device error, got {:?}", other),
+        }
+        match resolve("name:Nonexistent") {
+            Err(Error::NoMatchingDevice(_)) => (),
+            other => panic!("expected no matching device error, got {:?}", other),
+        }
+        Ok(())
+    }
+}
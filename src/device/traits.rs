@@ -1,10 +1,27 @@
 use crate::errors::Result;
-use crate::events::InputEvent;
+use crate::events::{InputEvent, KeyCode};
 
 pub trait InputEventSource: Send {
     fn recv(&mut self) -> Result<InputEvent>;
+
+    /// Returns the keys currently held down, which are read again from the device after the
+    /// kernel has dropped events. Sources that can't tell return `None`.
+    fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+        Ok(None)
+    }
+}
+
+impl<S: InputEventSource + ?Sized> InputEventSource for Box<S> {
+    fn recv(&mut self) -> Result<InputEvent> {
+        (**self).recv()
+    }
+
+    fn pressed_keys(&mut self) -> Result<Option<Vec<KeyCode>>> {
+        (**self).pressed_keys()
+    }
 }
 
 pub trait InputEventSink: Send {
-    fn send(&mut self, e: InputEvent) -> Result<()>;
+    /// Writes the events of one frame followed by a single `SYN_REPORT`.
+    fn send(&mut self, frame: &[InputEvent]) -> Result<()>;
 }
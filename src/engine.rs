@@ -1,109 +1,604 @@
-use async_std::channel::unbounded;
-use async_std::channel::Receiver;
-use async_std::channel::Sender;
-use async_std::prelude::FutureExt;
-use async_std::prelude::StreamExt;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
 use async_std::task;
 use log::debug;
 use log::error;
 use log::trace;
 
-use qsk_types::control_code::ControlCode;
-use qsk_types::layer_composer::InputTransformer;
-use crate::events::EventCode;
-use crate::events::InputEvent;
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::layer_composer::{InputTransformer, LayerChange};
+use qsk_types::layers::LayerInfo;
+use crate::control::{ControlSocket, Event, Request, Response, Server, Subscribers};
+use crate::events::{EventCode, KeyCode, KeyState};
+use crate::device::cancel::Cancel;
+use crate::device::frames::{Frame, FrameBuilder};
+use crate::device::reader::{Reader, Stopped};
 use crate::device::traits::InputEventSink;
 use crate::device::traits::InputEventSource;
+use crate::errors::Result;
+use crate::inject::{self, Chord, Injector};
+use crate::latency::{Latency, Stamps};
+use crate::realtime::Realtime;
+use crate::signals::Signals;
+
+// Room for the output of any one frame, allocated before the first event arrives.
+const OUTPUT_CAPACITY: usize = 256;
+
+/// What the handler thread receives: frames from the readers and requests from the control
+/// socket, in the order they arrived, and a notice from each reader as it stops.
+pub enum Input {
+    Frame(Frame, Stamps),
+    Control(Request, Sender<Response>),
+    Stopped(usize),
+}
+
+impl From<(Frame, Stamps)> for Input {
+    fn from((frame, stamps): (Frame, Stamps)) -> Self {
+        Input::Frame(frame, stamps)
+    }
+}
+
+impl From<Stopped> for Input {
+    fn from(Stopped(device): Stopped) -> Self {
+        Input::Stopped(device)
+    }
+}
+
+impl From<(Request, Sender<Response>)> for Input {
+    fn from((request, sender): (Request, Sender<Response>)) -> Self {
+        Input::Control(request, sender)
+    }
+}
 
 pub struct QSKEngine {
     input_transformer: Box<dyn InputTransformer + Send>,
+    latency: Option<Latency>,
+    realtime: Option<Realtime>,
+    control: Option<ControlSocket>,
+    signals: Option<Signals>,
+    // while paused, events are written unchanged rather than transformed, save for those of
+    // `toggle_keys`
+    paused: bool,
+    toggle_keys: ToggleKeys,
+    subscribers: Subscribers,
+    injector: Injector,
+    // readers still running; the control socket and signals keep the channel open without them
+    readers: usize,
 }
 
 impl QSKEngine {
     pub fn new(it: Box<dyn InputTransformer + Send>) -> Self {
         QSKEngine {
             input_transformer: it,
+            latency: None,
+            realtime: None,
+            control: None,
+            signals: None,
+            paused: false,
+            toggle_keys: ToggleKeys::default(),
+            subscribers: Subscribers::default(),
+            injector: Injector::default(),
+            readers: 0,
         }
     }
 
-    pub async fn handle(mut self, mut r: Receiver<InputEvent>, s: Sender<InputEvent>) {
-        while let Some(e) = r.next().await {
-            match e.code {
-                EventCode::SynCode(_) => trace!("recv: {:?} {:?}", e.code, e.state),
-                _ => debug!("recv: {:?} {:?}", e.code, e.state),
-            };
-            if let Some(e_vec) = self.input_transformer.transform(e) {
-                for cc in e_vec.iter() {
-                    match cc {
-                        ControlCode::InputEvent(v) => {
-                            if let Err(e) = s.send(v.clone()).await {
-                                error!("error sending: {:?}", e);
-                                return;
-                            }
-                            match e.code {
-                                EventCode::SynCode(_) => trace!("recv: {:?} {:?}", v.code, v.state),
-                                _ => debug!("send: {:?} {:?}", v.code, v.state),
-                            };
+    /// Records how long frames take through each stage of the engine in `latency`.
+    pub fn with_latency(mut self, latency: Latency) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Runs the reader threads and the handler thread with realtime priority.
+    pub fn with_realtime(mut self, realtime: Realtime) -> Self {
+        self.realtime = Some(realtime);
+        self
+    }
+
+    /// Takes requests from scripts on the given socket; see `Request`.
+    pub fn with_control(mut self, control: ControlSocket) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    /// Starts out passing events through, until passthrough is toggled or remapping resumed.
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self.toggle_keys = ToggleKeys::new(&self.input_transformer.layers());
+        self
+    }
+
+    /// Toggles passthrough on `SIGUSR1`.
+    pub fn with_signals(mut self, signals: Signals) -> Self {
+        self.signals = Some(signals);
+        self
+    }
+
+    /// Transforms the frames received from the readers and writes the output to `snk`, until
+    /// the transformer or a control request asks to exit or every reader has stopped.
+    pub fn handle(mut self, r: Receiver<Input>, mut snk: Box<dyn InputEventSink>) {
+        if let Some(realtime) = &self.realtime {
+            realtime.enter();
+        }
+        // the transformer's output is collected in the same buffer for every frame and tick
+        let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+        loop {
+            // wait for input no longer than until the next tick so that time-driven output, like
+            // the pointer movement of held mouse keys or injected keystrokes, keeps flowing
+            let next = match self.tick_interval() {
+                Some(interval) => match r.recv_timeout(interval) {
+                    Ok(next) => Some(next),
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !self.tick(&mut output, snk.as_mut()) {
+                            return;
                         }
-                        ControlCode::Exit => return,
-                        _ => continue,
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => None,
+                },
+                None => r.recv().ok(),
+            };
+            let (frame, mut stamps) = match next {
+                Some(Input::Frame(frame, stamps)) => (frame, stamps),
+                Some(Input::Control(request, sender)) => {
+                    if !self.control(request, &sender, snk.as_mut()) {
+                        return;
                     }
+                    self.subscribers.update_layers(self.input_transformer.active_layers());
+                    continue;
+                }
+                Some(Input::Stopped(device)) => {
+                    self.readers = self.readers.saturating_sub(1);
+                    debug!("reader for device {} stopped, {} left", device, self.readers);
+                    if self.readers == 0 {
+                        return;
+                    }
+                    continue;
+                }
+                None => return,
+            };
+            // the output of a whole input frame is written together, so that eg a modifier and
+            // the key pressed with it reach applications in the same report
+            output.clear();
+            stamps.transform_started();
+            for e in frame {
+                // non-key events like MSC_SCAN and mouse movement are frequent enough to drown
+                // out key events at debug level
+                match e.code {
+                    EventCode::KeyCode(_) => debug!("recv: {:?} {:?}", e.code, e.state),
+                    _ => trace!("recv: {:?} {:?}", e.code, e.value),
+                };
+                if !self.paused {
+                    self.input_transformer.transform(e, &mut output);
+                    continue;
                 }
+                // the transformer is left alone while paused, its timers stopped, so nothing
+                // but the keys switching back is looked out for
+                match e.code {
+                    EventCode::KeyCode(kc)
+                        if e.state == KeyState::Down
+                            && self.toggle_keys.matches(kc, |held| self.injector.is_held(held)) =>
+                    {
+                        output.push(ControlCode::TogglePassthrough)
+                    }
+                    _ => output.push(ControlCode::InputEvent(e)),
+                }
+            }
+            stamps.transform_ended();
+            if let Some(latency) = &self.latency {
+                latency.transformed(&stamps);
+            }
+            let running = self.write_control_codes(&output, Some(stamps), snk.as_mut());
+            self.subscribers.update_layers(self.input_transformer.active_layers());
+            if !running {
+                return;
+            }
+            // a steady stream of input must not starve time-driven output either
+            if !self.tick(&mut output, snk.as_mut()) {
+                return;
+            }
+        }
+    }
+
+    // The transformer's time-driven output stops while paused; injected keystrokes don't.
+    fn tick_interval(&self) -> Option<Duration> {
+        let transformer = match self.paused {
+            true => None,
+            false => self.input_transformer.tick_interval(),
+        };
+        match (transformer, self.injector.interval(Instant::now())) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    // Writes the time-driven output that is due, returning false once the engine should stop.
+    // Transformers keep track of their own intervals, so ticking one early is harmless.
+    fn tick(&mut self, output: &mut Vec<ControlCode>, snk: &mut dyn InputEventSink) -> bool {
+        if !self.paused && self.input_transformer.tick_interval().is_some() {
+            output.clear();
+            self.input_transformer.tick(output);
+            if !self.write_control_codes(output, None, snk) {
+                return false;
+            }
+        }
+        // a keystroke is written in one go, so the user's own input can't land in the middle of
+        // it and find its modifiers lifted
+        if let Some(frames) = self.injector.next_keystroke(Instant::now()) {
+            self.write_frames(&frames, snk);
+        }
+        true
+    }
+
+    // Carries out a request from the control socket and answers it, returning false once the
+    // engine should stop.
+    fn control(
+        &mut self,
+        request: Request,
+        sender: &Sender<Response>,
+        snk: &mut dyn InputEventSink,
+    ) -> bool {
+        let mut running = true;
+        let response = match request {
+            Request::Status => Response::Status {
+                paused: self.paused,
+                layers: self.input_transformer.layers().into_iter().map(Into::into).collect(),
+            },
+            Request::Keymap => Response::Keymap {
+                layers: self.input_transformer.layers(),
+            },
+            Request::Activate { layer } => self.change_layer(layer, LayerChange::Activate),
+            Request::Deactivate { layer } => self.change_layer(layer, LayerChange::Deactivate),
+            Request::Toggle { layer } => self.change_layer(layer, LayerChange::Toggle),
+            Request::Pause => self.set_paused(true, snk),
+            Request::Resume => self.set_paused(false, snk),
+            Request::TogglePassthrough => self.set_paused(!self.paused, snk),
+            Request::Reset => {
+                // nothing held may be left stuck on the virtual device once the composer has
+                // forgotten about it
+                if let Some(release) = self.injector.release_all() {
+                    self.write_frames(&[release], snk);
+                }
+                self.input_transformer.reset();
+                Response::Ok
+            }
+            Request::Exit => {
+                running = false;
+                Response::Ok
+            }
+            Request::Type { text } => self.inject(inject::text_chords(&text)),
+            Request::Key { chords } => {
+                self.inject(chords.iter().map(|c| inject::parse_chord(c)).collect())
+            }
+            Request::Subscribe => {
+                let layers = self.input_transformer.layers();
+                let active = self.input_transformer.active_layers();
+                self.subscribers.add(sender.clone(), &layers, active);
+                Response::Status {
+                    paused: self.paused,
+                    layers: layers.into_iter().map(Into::into).collect(),
+                }
+            }
+        };
+        debug!("control response: {:?}", response);
+        // the client may have hung up in the meantime, which is no reason to stop
+        let _ = sender.send(response);
+        running
+    }
+
+    // Switches between transforming and passing events through. Keys held at the switch are
+    // released, so that nothing pressed on one side is left stuck on the other, and the
+    // transformer is reset, since it saw nothing of what passed through. It is reset on the way
+    // in too, so that the keys switching back are those of the layers active from the start
+    // rather than of one that happened to be held for the switch.
+    fn set_paused(&mut self, paused: bool, snk: &mut dyn InputEventSink) -> Response {
+        if self.paused != paused {
+            if let Some(release) = self.injector.release_all() {
+                self.write_frames(&[release], snk);
+            }
+            self.input_transformer.reset();
+            if paused {
+                self.toggle_keys = ToggleKeys::new(&self.input_transformer.layers());
+            }
+            self.paused = paused;
+            debug!("passthrough {}", if paused { "on" } else { "off" });
+            self.subscribers.publish(Event::Paused { paused });
+        }
+        Response::Ok
+    }
+
+    fn inject(&mut self, chords: Result<Vec<Chord>>) -> Response {
+        match chords {
+            Ok(chords) => {
+                self.injector.queue(chords);
+                Response::Ok
             }
+            Err(err) => Response::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+
+    fn change_layer(&mut self, layer: String, change: LayerChange) -> Response {
+        let layer_ref = LayerRef::ByName(layer.clone());
+        match self.input_transformer.change_layer(&layer_ref, change) {
+            Ok(active) => Response::Layer { layer, active },
+            Err(err) => Response::Error {
+                message: err.to_string(),
+            },
         }
     }
 
     pub async fn run(
-        self,
-        mut src: Box<dyn InputEventSource>,
-        mut snk: Box<dyn InputEventSink>,
+        mut self,
+        srcs: Vec<Box<dyn InputEventSource>>,
+        snk: Box<dyn InputEventSink>,
+        cancel: Cancel,
     ) -> std::result::Result<(), Box<dyn std::error::Error>> {
-        let (input_sender, handler_receiver) = unbounded();
-        let (handler_sender, mut output_receiver) = unbounded();
+        let (input_sender, handler_receiver) = channel();
+        let realtime = self.realtime;
+        let control = self.control.take();
+        let signals = self.signals.take();
+        self.readers = srcs.len();
+        // memory is locked before any of the threads are spawned so that their stacks are too
+        if let Some(realtime) = &realtime {
+            realtime.lock_memory();
+        }
 
-        trace!("creating handler task");
-        let handler_task = task::Builder::new()
+        // events pass through threads rather than tasks from being read to being written, so that
+        // they can be scheduled ahead of everything else with --realtime
+        trace!("creating handler thread");
+        let handler = thread::Builder::new()
             .name("handler".to_string())
-            .spawn(self.handle(handler_receiver, handler_sender))?;
-
-        trace!("creating input task");
-        let input_task = task::Builder::new()
-            .name("input".to_string())
-            .spawn(async move {
-                loop {
-                    let t = src.recv();
-                    trace!("received InputEvent from keyboard");
-                    match t {
-                        Ok(a) => match input_sender.send(a).await {
-                            Err(async_std::channel::SendError(msg)) => {
-                                debug!("channel closed, failed to send: {:?}", msg);
-                                break;
-                            }
-                            _ => (),
-                        },
-                        Err(err) => {
-                            error!("error reading from keyboard device: {:?}", err)
-                        }
-                    }
-                    trace!("sent InputEvent to handler");
+            .spawn(move || self.handle(handler_receiver, snk))?;
+
+        // each device is read on a thread of its own; frames from every device are merged into
+        // the handler's channel
+        let mut readers = Vec::new();
+        for (i, src) in srcs.into_iter().enumerate() {
+            trace!("creating reader thread for device {}", i);
+            readers.push(Reader::spawn(i, src, input_sender.clone(), realtime)?);
+        }
+
+        // requests from scripts are merged into the handler's channel as well
+        let server = control
+            .map(|control| control.serve(input_sender.clone(), cancel.clone()))
+            .transpose()?;
+        let signals = signals
+            .map(|signals| signals.serve(input_sender.clone(), cancel.clone()))
+            .transpose()?;
+        drop(input_sender);
+
+        task::spawn_blocking(move || {
+            if handler.join().is_err() {
+                error!("handler thread panicked");
+            }
+            // the sources, the control socket and the signal pipe are waited on with `cancel`, so
+            // cancelling wakes up the threads still blocked on them
+            trace!("stopping reader, control and signal threads");
+            cancel.cancel();
+            readers.into_iter().for_each(Reader::join);
+            server.into_iter().for_each(Server::join);
+            if let Some(signals) = signals {
+                if signals.join().is_err() {
+                    error!("signals thread panicked");
+                }
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    // Writes the input events among the given control codes as frames, returning false once the
+    // engine should stop. The output of a frame has been written in full once its last frame has,
+    // which is when the latency of writing it is recorded.
+    fn write_control_codes(
+        &mut self,
+        ccs: &[ControlCode],
+        stamps: Option<Stamps>,
+        snk: &mut dyn InputEventSink,
+    ) -> bool {
+        let mut frames = FrameBuilder::default();
+        let mut running = true;
+        let mut toggle_passthrough = false;
+        for cc in ccs {
+            match cc {
+                ControlCode::InputEvent(v) => {
+                    match v.code {
+                        EventCode::KeyCode(_) => debug!("send: {:?} {:?}", v.code, v.state),
+                        _ => trace!("send: {:?} {:?}", v.code, v.value),
+                    };
+                    frames.push(*v);
                 }
-            })?;
-
-        trace!("creating output task");
-        let output_task = task::Builder::new()
-            .name("output".to_string())
-            .spawn(async move {
-                while let Some(e) = output_receiver.next().await {
-                    trace!("received InputEvent from handler");
-                    match snk.send(e) {
-                        Ok(_) => (),
-                        Err(err) => error!("error writing to keyboard device: {:?}", err),
+                ControlCode::TogglePassthrough => toggle_passthrough = true,
+                ControlCode::Exit => {
+                    running = false;
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        let frames = frames.finish();
+        self.write_frames(&frames, snk);
+        if let (Some(latency), Some(stamps)) = (&self.latency, stamps) {
+            if !frames.is_empty() {
+                latency.written(&stamps, Instant::now());
+            }
+        }
+        // the output preceding the switch is written first, so that it gets released too
+        if toggle_passthrough {
+            self.set_paused(!self.paused, snk);
+        }
+        running
+    }
+
+    // Every frame written to the virtual device goes through here, so that the injector knows
+    // which keys are held on it.
+    fn write_frames(&mut self, frames: &[Frame], snk: &mut dyn InputEventSink) {
+        for frame in frames {
+            if let Err(err) = snk.send(frame) {
+                error!("error writing to keyboard device: {:?}", err);
+            }
+            self.injector.track(frame);
+        }
+        trace!("sent {} frames to virtual keyboard", frames.len());
+    }
+}
+
+/// The keys that switch back from passthrough: those mapped to `TogglePassthrough()` on active
+/// layers, and on layers held active by another key, eg with `MO()`, along with that key.
+#[derive(Default)]
+struct ToggleKeys(Vec<(KeyCode, Option<KeyCode>)>);
+
+impl ToggleKeys {
+    fn new(layers: &[LayerInfo]) -> Self {
+        let resolve = |layer_ref: &LayerRef| match layer_ref {
+            LayerRef::ByIndex(i) => layers.get(*i),
+            LayerRef::ByName(name) => layers.iter().find(|l| &l.name == name),
+        };
+        let bound = |layer: &LayerInfo| -> Vec<KeyCode> {
+            layer
+                .keys
+                .iter()
+                .filter(|(_, ccs)| ccs.contains(&ControlCode::TogglePassthrough))
+                .map(|(kc, _)| *kc)
+                .collect()
+        };
+        let mut keys = Vec::new();
+        for layer in layers.iter().filter(|l| l.active) {
+            keys.extend(bound(layer).into_iter().map(|kc| (kc, None)));
+            for (held, ccs) in &layer.keys {
+                for cc in ccs {
+                    if let ControlCode::Momentary(r) | ControlCode::TapToggle(r, _) = cc {
+                        let reached = resolve(r).map(bound).unwrap_or_default();
+                        keys.extend(reached.into_iter().map(|kc| (kc, Some(*held))));
                     }
-                    trace!("sent InputEvent to virtual keyboard");
                 }
-            })?;
+            }
+        }
+        ToggleKeys(keys)
+    }
+
+    // Whether pressing `key` switches back, given which keys are held.
+    fn matches(&self, key: KeyCode, is_held: impl Fn(KeyCode) -> bool) -> bool {
+        self.0.iter().any(|(kc, with)| *kc == key && with.is_none_or(&is_held))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+    use std::sync::{Arc, Mutex};
+    use std::time::SystemTime;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+    use qsk_types::layer_composer::Passthrough;
+
+    use super::*;
+    use crate::errors::Error;
+    use crate::events::InputEvent;
+    use crate::events::KeyCode::*;
+
+    fn layer(name: &str, active: bool, keys: Vec<(KeyCode, ControlCode)>) -> LayerInfo {
+        LayerInfo {
+            name: name.to_string(),
+            active,
+            device: None,
+            keys: keys.into_iter().map(|(kc, cc)| (kc, vec![cc])).collect(),
+        }
+    }
+
+    #[test]
+    fn toggle_keys_are_found_on_active_and_held_layers() {
+        let layers = vec![
+            layer(
+                "base",
+                true,
+                vec![
+                    (KC_PAUSE, ControlCode::TogglePassthrough),
+                    (KC_CAPSLOCK, ControlCode::Momentary(LayerRef::ByName("fn".to_string()))),
+                ],
+            ),
+            layer("fn", false, vec![(KC_P, ControlCode::TogglePassthrough)]),
+            layer("numpad", false, vec![(KC_N, ControlCode::TogglePassthrough)]),
+        ];
+        let toggle_keys = ToggleKeys::new(&layers);
+
+        assert_that!(&toggle_keys.matches(KC_PAUSE, |_| false), eq(true));
+        // P switches back only while the key holding its layer is held, so that alone it types
+        assert_that!(&toggle_keys.matches(KC_P, |_| false), eq(false));
+        assert_that!(&toggle_keys.matches(KC_P, |kc| kc == KC_CAPSLOCK), eq(true));
+        // nothing reaches the numpad layer
+        assert_that!(&toggle_keys.matches(KC_N, |_| true), eq(false));
+    }
+
+    // A source whose every read fails.
+    struct Failing;
+
+    impl InputEventSource for Failing {
+        fn recv(&mut self) -> Result<InputEvent> {
+            Err(Error::NoEvents)
+        }
+    }
+
+    struct Discard;
+
+    impl InputEventSink for Discard {
+        fn send(&mut self, _: &[InputEvent]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    // A sink keeping the frames written to it where the test can look at them.
+    #[derive(Clone, Default)]
+    struct Recording(Arc<Mutex<Vec<Frame>>>);
+
+    impl InputEventSink for Recording {
+        fn send(&mut self, frame: &[InputEvent]) -> Result<()> {
+            self.0.lock().unwrap().push(frame.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pause_is_written_while_paused() {
+        // Passthrough would turn PAUSE into Exit, were it asked
+        let engine = QSKEngine::new(Box::new(Passthrough {})).with_paused(true);
+        let written = Recording::default();
+        let (sender, receiver) = channel();
+        let pause = InputEvent {
+            time: SystemTime::now(),
+            code: EventCode::KeyCode(KC_PAUSE),
+            state: KeyState::Down,
+            value: 1,
+            device: 0,
+        };
+        sender.send(Input::Frame(vec![pause], Stamps::read(SystemTime::now()))).unwrap();
+        drop(sender);
+        engine.handle(receiver, Box::new(written.clone()));
+
+        let frames = written.0.lock().unwrap();
+        let codes: Vec<EventCode> = frames.iter().flatten().map(|e| e.code).collect();
+        assert_that!(&codes, eq(vec![EventCode::KeyCode(KC_PAUSE)]));
+    }
+
+    #[test]
+    fn engine_stops_once_every_reader_has() -> Result<()> {
+        let path = env::temp_dir().join(format!("qsk-test-engine-{}.sock", process::id()));
+        let cancel = Cancel::new()?;
+        let engine = QSKEngine::new(Box::new(Passthrough {})).with_control(ControlSocket::bind(&path)?);
+        let srcs: Vec<Box<dyn InputEventSource>> = vec![Box::new(Failing), Box::new(Failing)];
 
-        input_task.race(output_task).race(handler_task).await;
+        // the control server holds on to the handler's channel, so it never closes on its own
+        let (done, stopped) = channel();
+        thread::spawn(move || {
+            let _ = task::block_on(engine.run(srcs, Box::new(Discard), cancel));
+            let _ = done.send(());
+        });
+        assert_that!(&stopped.recv_timeout(Duration::from_secs(5)).is_ok(), eq(true));
         Ok(())
     }
 }
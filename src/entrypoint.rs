@@ -1,4 +1,7 @@
 use std::error;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -8,30 +11,67 @@ use async_std::task;
 use clap::ArgMatches;
 
 use qsk_types::layer_composer::{
-    LayerComposer, InputTransformer, Passthrough,
+    LayerComposer, InputTransformer,
 };
 
 use crate::cli::get_clap_app;
-use crate::device::linux::Device;
-use crate::device::linux_evdev;
+use crate::control::{self, ControlSocket, Request, Response};
+use crate::device::backend::OutputConfig;
+use crate::device::cancel::Cancel;
+use crate::device::gamepad::{AxisConfig, Gamepad};
+use crate::device::hotplug::{self, DirectoryWatcher, Reconnecting};
+use crate::device::info::{DeviceInfo, DEFAULT_MIN_KEYS};
+use crate::device::selector::DeviceSelector;
+use crate::device::traits::InputEventSource;
+use crate::device::PlatformDevice;
 use crate::engine::QSKEngine;
+use crate::errors;
+use crate::events::{DeviceId, KeyCode};
+use crate::formats::{dsl, kanata, qmk};
+use crate::latency::Latency;
+use crate::realtime::Realtime;
 use crate::listener::StdoutListener;
 use crate::recorder::Recorder;
+use crate::signals::Signals;
 
 pub fn entrypoint(lc: LayerComposer) -> Result<(), Box<dyn error::Error>> {
     let matches = get_clap_app()?;
 
     match matches.subcommand() {
         Some(("listen", submatches)) => task::block_on(Compat::new(listen(submatches)))?,
-        Some(("list-devices", _)) => linux_evdev::Device::list()?,
+        Some(("list-devices", submatches)) => list_devices(submatches)?,
+        Some(("inspect-device", submatches)) => inspect_device(submatches)?,
         Some(("remap", submatches)) => task::block_on(remap(lc, submatches))?,
+        Some(("import", submatches)) => import(submatches)?,
+        Some(("export", submatches)) => export(&lc, submatches)?,
+        Some(("ctl", submatches)) => ctl(submatches)?,
+        Some(("type", submatches)) => inject(submatches, Request::Type {
+            text: submatches.value_of("text").unwrap_or_default().to_string(),
+        })?,
+        Some(("key", submatches)) => inject(submatches, Request::Key {
+            chords: submatches.values_of("chord").into_iter().flatten().map(String::from).collect(),
+        })?,
         _ => (),
     };
     Ok(())
 }
 
 async fn remap(lc: LayerComposer, matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
-    let input_events_file = matches.value_of_t("device-file")?;
+    // devices named on the command line come first, followed by those passed with --device
+    let input_events_files = matches
+        .values_of("device-file")
+        .into_iter()
+        .flatten()
+        .chain(matches.values_of("device").into_iter().flatten())
+        .map(|s| resolve(&s.parse()?))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+
+    // the socket is bound before any device is grabbed so that a second qsk fails right away
+    let mut control = match matches.is_present("no-control-socket") {
+        true => None,
+        false => Some(ControlSocket::bind(&control_socket_path(matches, "control-socket"))?),
+    };
+    let mut signals = Some(Signals::install()?);
 
     // give input source events time to finish before grabbing. this is necessary if the keyboard
     // being remapped is the one where "enter" is pressed on the command line to call `qsk` in the
@@ -39,34 +79,251 @@ async fn remap(lc: LayerComposer, matches: &ArgMatches) -> Result<(), Box<dyn er
     //
     sleep(Duration::from_millis(300));
 
-    let myd = Device::from_path(input_events_file)?;
-    let ui = myd.new_uinput_device()?;
+    let mut config = AxisConfig::default();
+    if matches.is_present("deadzone") {
+        config.deadzone = matches.value_of_t("deadzone")?;
+    }
+    if matches.is_present("threshold") {
+        config.threshold = matches.value_of_t("threshold")?;
+    }
+    if matches.is_present("hysteresis") {
+        config.hysteresis = matches.value_of_t("hysteresis")?;
+    }
+
+    // devices are identified by their position on the command line so that layers can be scoped
+    // to them; the virtual device is modeled after the first one. Reading from them is cancelled
+    // once the engine exits.
+    let cancel = Cancel::new()?;
+    let devices = input_events_files
+        .iter()
+        .enumerate()
+        .map(|(id, path)| {
+            Ok(PlatformDevice::open(path)?
+                .with_id(id as DeviceId)
+                .with_cancel(cancel.clone()))
+        })
+        .collect::<errors::Result<Vec<PlatformDevice>>>()?;
+    let first = devices.first().ok_or("no device files given")?;
+    let ui = first.new_uinput_device(&output_config(matches, &lc, &devices)?)?;
 
-    let mut transformer: Box<dyn InputTransformer + Send>;
-    transformer = Box::new(Passthrough {});
-    if !matches.is_present("passthrough") {
-        transformer = Box::new(lc);
+    let mut sources: Vec<Box<dyn InputEventSource>> = Vec::new();
+    for myd in devices {
+        let ranges = myd.axis_ranges();
+        let watcher = DirectoryWatcher::new(hotplug::INPUT_DIR)?.with_cancel(cancel.clone());
+        let myd = Reconnecting::new(myd, watcher);
+        let source: Box<dyn InputEventSource> = match matches.is_present("gamepad") {
+            true => Box::new(Gamepad::new(myd, ranges, config)),
+            false => Box::new(myd),
+        };
+        sources.push(source);
     }
 
+    // with --passthrough the keymap is still loaded, so that remapping can be switched on later
+    let transformer: Box<dyn InputTransformer + Send> = Box::new(lc);
+    let paused = matches.is_present("passthrough");
+
+    // latency is only recorded when it's reported, either on exit or periodically at debug level
+    let latency = (matches.is_present("latency-report") || log::log_enabled!(log::Level::Debug))
+        .then(Latency::new);
+    let realtime = matches.is_present("realtime");
+    let mut new_engine = |transformer: Box<dyn InputTransformer + Send>| {
+        let mut engine = QSKEngine::new(transformer).with_paused(paused);
+        if let Some(control) = control.take() {
+            engine = engine.with_control(control);
+        }
+        if let Some(signals) = signals.take() {
+            engine = engine.with_signals(signals);
+        }
+        if let Some(latency) = &latency {
+            engine = engine.with_latency(latency.clone());
+        }
+        if realtime {
+            engine = engine.with_realtime(Realtime::default());
+        }
+        engine
+    };
+
     if let Some(path) = matches.value_of("log-keys-to") {
         let (mut recorder, listener) = Recorder::wrap(transformer);
-        let engine = QSKEngine::new(Box::new(listener));
-        let engine_task = engine.run(Box::new(myd), Box::new(ui));
+        let engine = new_engine(Box::new(listener));
+        let engine_task = engine.run(sources, Box::new(ui), cancel);
         let recorder_task = recorder.record(path.into());
         engine_task.race(recorder_task).await?
     } else {
-        let engine = QSKEngine::new(transformer);
-        engine.run(Box::new(myd), Box::new(ui)).await?;
+        let engine = new_engine(transformer);
+        engine.run(sources, Box::new(ui), cancel).await?;
     }
 
+    if let Some(latency) = latency.filter(|_| matches.is_present("latency-report")) {
+        print!("{}", latency);
+    }
     Ok(())
 }
 
+// By default the virtual device supports the keys of every device being remapped and every key
+// the layers can emit, so that remapped keys the first device lacks can still be delivered.
+fn output_config(
+    matches: &ArgMatches,
+    lc: &LayerComposer,
+    devices: &[PlatformDevice],
+) -> errors::Result<OutputConfig> {
+    let invalid = |option: &str, value: &str| errors::Error::InvalidOption {
+        option: option.to_string(),
+        value: value.to_string(),
+    };
+    let hex = |option: &str, value: &str| {
+        u16::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| invalid(option, value))
+    };
+
+    let mut config = OutputConfig {
+        name: matches.value_of("output-name").map(String::from),
+        extra_keys: devices
+            .iter()
+            .flat_map(|device| device.keys())
+            .chain(lc.output_keys().into_iter().map(|kc| kc as u16))
+            .collect(),
+        ..OutputConfig::default()
+    };
+    if let Some(id) = matches.value_of("output-id") {
+        let (vendor, product) = id.split_once(':').ok_or_else(|| invalid("output-id", id))?;
+        config.vendor = Some(hex("output-id", vendor)?);
+        config.product = Some(hex("output-id", product)?);
+    }
+    if let Some(bus) = matches.value_of("output-bus") {
+        config.bustype = Some(hex("output-bus", bus)?);
+    }
+    if let Some(keys) = matches.values_of("output-keys") {
+        config.keys = Some(
+            keys.map(|key| match key.parse::<KeyCode>() {
+                Ok(kc) => Ok(kc as u16),
+                Err(_) => Err(invalid("output-keys", key)),
+            })
+            .collect::<errors::Result<Vec<u16>>>()?,
+        );
+    }
+    Ok(config)
+}
+
 async fn listen(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
-    let input_events_file = matches.value_of_t("device-file")?;
-    let myd = Device::from_path(input_events_file)?;
+    let selector: DeviceSelector = matches.value_of_t("device-file")?;
+    let myd = PlatformDevice::open(&resolve(&selector)?)?;
     let mut listener = StdoutListener::from_device(myd);
     listener.listen();
 
     Ok(())
 }
+
+fn control_socket_path(matches: &ArgMatches, option: &str) -> PathBuf {
+    match matches.value_of(option) {
+        Some(path) => path.into(),
+        None => control::default_path(),
+    }
+}
+
+fn ctl(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let path = control_socket_path(matches, "socket");
+    // events are printed a line each as they arrive, for status bars to read
+    if matches.value_of("command") == Some("subscribe") {
+        for response in control::subscribe(&path)? {
+            println!("{}", serde_json::to_string(&response?)?);
+        }
+        return Ok(());
+    }
+
+    let layer = || matches.value_of("layer").unwrap_or_default().to_string();
+    let request = match matches.value_of("command") {
+        Some("status") => Request::Status,
+        Some("keymap") => Request::Keymap,
+        Some("activate") => Request::Activate { layer: layer() },
+        Some("deactivate") => Request::Deactivate { layer: layer() },
+        Some("toggle") => Request::Toggle { layer: layer() },
+        Some("pause") => Request::Pause,
+        Some("resume") => Request::Resume,
+        Some("toggle-passthrough") => Request::TogglePassthrough,
+        Some("reset") => Request::Reset,
+        _ => Request::Exit,
+    };
+    match control::request(&path, &request)? {
+        Response::Ok => (),
+        Response::Error { message } => return Err(message.into()),
+        response => println!("{}", serde_json::to_string_pretty(&response)?),
+    }
+    Ok(())
+}
+
+// Keystrokes are queued by the remapper, so this returns before they have all been typed.
+fn inject(matches: &ArgMatches, request: Request) -> Result<(), Box<dyn error::Error>> {
+    match control::request(&control_socket_path(matches, "socket"), &request)? {
+        Response::Error { message } => Err(message.into()),
+        _ => Ok(()),
+    }
+}
+
+fn list_devices(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let min_keys = match matches.value_of("min-keys") {
+        Some(_) => matches.value_of_t("min-keys")?,
+        None => DEFAULT_MIN_KEYS,
+    };
+    let all = matches.is_present("all");
+    let infos: Vec<DeviceInfo> = PlatformDevice::enumerate()
+        .into_iter()
+        .filter(|info| all || info.is_listed(min_keys))
+        .collect();
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&infos)?);
+    } else {
+        infos.iter().for_each(|info| print!("{}", info));
+    }
+    Ok(())
+}
+
+fn inspect_device(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let selector: DeviceSelector = matches.value_of_t("device-file")?;
+    let inspection = PlatformDevice::inspect(&resolve(&selector)?)?;
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+    } else {
+        print!("{}", inspection);
+    }
+    Ok(())
+}
+
+fn resolve(selector: &DeviceSelector) -> errors::Result<PathBuf> {
+    selector.resolve(&PlatformDevice::enumerate())
+}
+
+fn import(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let path: PathBuf = matches.value_of_t("keymap-file")?;
+    let (composer, unsupported): (LayerComposer, Vec<String>) = match matches.value_of("from") {
+        Some("qmk") => {
+            let import = qmk::import(path)?;
+            (import.composer, import.unsupported.iter().map(|u| u.to_string()).collect())
+        }
+        _ => {
+            let import = kanata::import(path)?;
+            (import.composer, import.unsupported.iter().map(|u| u.to_string()).collect())
+        }
+    };
+
+    for u in unsupported.iter() {
+        eprintln!("unsupported: {}", u);
+    }
+    print!("{}", dsl::to_remap_macro(&composer));
+    Ok(())
+}
+
+fn export(lc: &LayerComposer, matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let layout: String = matches.value_of_t("layout")?;
+    let mut export = qmk::export(lc, &layout)?;
+    export.keymap.keyboard = matches.value_of("keyboard").map(String::from);
+
+    for warning in export.warnings.iter() {
+        eprintln!("warning: {}", warning);
+    }
+
+    match matches.value_of("output") {
+        Some(path) => export.keymap.write(File::create(path)?)?,
+        None => export.keymap.write(io::stdout())?,
+    };
+    Ok(())
+}
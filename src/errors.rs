@@ -1,7 +1,9 @@
 use async_std;
 use thiserror;
 
+#[cfg(feature = "backend-evdev")]
 use evdev;
+#[cfg(feature = "backend-evdev-rs")]
 use evdev_rs;
 
 use crate::events;
@@ -29,12 +31,86 @@ pub enum Error {
     #[error("unrecognized InputEvent\n time: {:?}, code: {:?}, value: {:?}", .e.time, .e.code, .e.state)]
     UnrecognizedInputEvent { e: events::InputEvent },
 
+    #[cfg(feature = "backend-evdev")]
     #[error("unrecognized evdev::InputEvent:\n time: {:?}, code: {:?}, value: {:?}", .e.timestamp(), .e.code(), .e.value())]
     UnrecognizedEvdevInputEvent { e: evdev::InputEvent },
 
+    #[cfg(feature = "backend-evdev-rs")]
     #[error("unrecognized evdev_rs::InputEvent:\n time: {:?}, type: {:?}, code: {:?}, value: {:?}", .e.time, .e.event_type(), .e.event_code, .e.value)]
     UnrecognizedEvdevRSInputEvent { e: evdev_rs::InputEvent },
 
     #[error("time error")]
     SystemTimeError(#[from] std::time::SystemTimeError),
+
+    #[error("json error")]
+    Json(#[from] serde_json::Error),
+
+    #[error("layer error")]
+    Layer(#[from] qsk_types::errors::Error),
+
+    #[error("parse error on line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("unknown layout: {0}")]
+    UnknownLayout(String),
+
+    #[error("layer {layer} has {found} keys but layout {layout} has {expected}")]
+    LayoutMismatch {
+        layout: String,
+        layer: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("invalid device selector: {0}, expected a path, name:<name>, id:<vendor>:<product> or phys:<path>")]
+    InvalidDeviceSelector(String),
+
+    #[error("invalid value for --{option}: {value}")]
+    InvalidOption { option: String, value: String },
+
+    #[error("no device matches {0}")]
+    NoMatchingDevice(String),
+
+    #[error("several devices match {selector}:\n{candidates}")]
+    AmbiguousDevice { selector: String, candidates: String },
+
+    #[error("reading was cancelled")]
+    Cancelled,
+
+    #[error("control socket {0} is in use, is qsk already running?")]
+    ControlSocketInUse(std::path::PathBuf),
+
+    #[error("{0} is in the way of the control socket and isn't a socket")]
+    ControlSocketNotASocket(std::path::PathBuf),
+
+    #[error("could not connect to {path}, is qsk remap running?")]
+    ControlConnect {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("unknown key: {0}")]
+    UnknownKey(String),
+
+    #[error("cannot type {0:?}, only characters on a US keyboard can be typed")]
+    UntypeableChar(char),
+}
+
+impl Error {
+    /// Returns true if the error means that the input device has been unplugged.
+    pub fn is_disconnected(&self) -> bool {
+        match self {
+            // ENODEV
+            Error::IO(e) => e.raw_os_error() == Some(19),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the error means that a non-blocking read found nothing to read.
+    pub fn is_would_block(&self) -> bool {
+        match self {
+            Error::IO(e) => e.kind() == std::io::ErrorKind::WouldBlock,
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::layer_composer::LayerComposer;
+
+use crate::events::{EventCode, KeyCode};
+
+/// Renders a `LayerComposer` as a `qsk_macros::remap!` invocation that can be pasted into a qsk
+/// project. Mappings the DSL can't express are rendered as comments.
+pub fn to_remap_macro(lc: &LayerComposer) -> String {
+    let names: Vec<String> = lc.iter().map(|layer| layer_name(&layer.name)).collect();
+    let layer_ref = |lr: &LayerRef| match lr {
+        LayerRef::ByIndex(i) => names[*i].clone(),
+        LayerRef::ByName(name) => layer_name(name),
+    };
+
+    let mut out = String::from("qsk_macros::remap!(\n");
+    for (layer, name) in lc.iter().zip(names.iter()) {
+        let opts: Vec<String> = layer
+            .active
+            .then(|| "Active".to_string())
+            .into_iter()
+            .chain(layer.device.map(|d| format!("Device({})", d)))
+            .collect();
+        let opts = match opts[..] {
+            [] => String::new(),
+            _ => format!("[{}]", opts.join(", ")),
+        };
+        writeln!(out, "    {}{}: {{", name, opts).unwrap();
+
+        // layers iterate in key code order, which keeps the output stable
+        for (kc, ccs) in layer.iter() {
+            let rhs = match &ccs[..] {
+                [ControlCode::KeyMap(kc)] => Some(key_name(*kc)),
+                [ControlCode::TapToggle(lr, kc)] => Some(format!("TT({}, {})", layer_ref(lr), key_name(*kc))),
+                [ControlCode::Momentary(lr)] => Some(format!("MO({})", layer_ref(lr))),
+                [ControlCode::Toggle(lr)] => Some(format!("TG({})", layer_ref(lr))),
+                [ControlCode::Switch(lr)] => Some(format!("TO({})", layer_ref(lr))),
+                [ControlCode::ModTap(modifier, kc)] => {
+                    Some(format!("MT({}, {})", key_name(*modifier), key_name(*kc)))
+                }
+                [ControlCode::MouseMove(dx, dy)] => Some(format!("MouseMove({}, {})", dx, dy)),
+                [ControlCode::MouseWheel(n)] => Some(format!("MouseWheel({})", n)),
+                [ControlCode::MouseButton(kc)] => Some(format!("MouseButton({})", key_name(*kc))),
+                [ControlCode::TogglePassthrough] => Some("TogglePassthrough()".to_string()),
+                [ControlCode::Exit] => Some("Exit()".to_string()),
+                _ => None,
+            };
+            match rhs {
+                Some(rhs) => writeln!(out, "        {} -> {},", key_name(kc), rhs).unwrap(),
+                None => {
+                    let ec = EventCode::KeyCode(kc);
+                    writeln!(out, "        // {:?} -> {:?} can't be expressed in remap!", ec, ccs).unwrap()
+                }
+            }
+        }
+        out.push_str("    },\n");
+    }
+    out.push_str(")\n");
+    out
+}
+
+// The DSL accepts key codes with or without their KC_ prefix, but only identifiers can appear
+// without it.
+fn key_name(kc: KeyCode) -> String {
+    let name = kc.to_string();
+    match name.strip_prefix("KC_") {
+        Some(short) if !short.starts_with(|c: char| c.is_ascii_digit()) => short.to_string(),
+        _ => name,
+    }
+}
+
+fn layer_name(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+    use maplit::hashmap;
+
+    use qsk_types::layers::Layer;
+
+    use super::*;
+    use crate::events::KeyCode::*;
+
+    #[test]
+    fn render_remap_macro() {
+        let lc = LayerComposer::from_layers(vec![
+            Layer::from_hashmap(
+                "base".to_string(),
+                hashmap!(
+                    KC_F => vec![ControlCode::TapToggle(LayerRef::ByIndex(1), KC_F)],
+                    KC_A => vec![ControlCode::ModTap(KC_LEFTCTRL, KC_A)],
+                    KC_1 => vec![ControlCode::KeyMap(KC_2)],
+                ),
+                true,
+            ),
+            Layer::from_hashmap(
+                "vim-nav".to_string(),
+                hashmap!(
+                    KC_H => vec![ControlCode::KeyMap(KC_LEFT)],
+                    KC_ESC => vec![ControlCode::Switch(LayerRef::ByName("base".to_string()))],
+                    KC_END => vec![ControlCode::Exit, ControlCode::Exit],
+                ),
+                false,
+            ),
+            {
+                let mut numpad = Layer::from_hashmap(
+                    "numpad".to_string(),
+                    hashmap!(KC_KP1 => vec![ControlCode::KeyMap(KC_F1)]),
+                    true,
+                );
+                numpad.scope_to_device(1);
+                numpad
+            },
+        ])
+        .unwrap();
+
+        let rendered = to_remap_macro(&lc);
+        assert_that!(
+            &rendered.as_str(),
+            eq("qsk_macros::remap!(
+    base[Active]: {
+        KC_1 -> KC_2,
+        A -> MT(LEFTCTRL, A),
+        F -> TT(vim_nav, F),
+    },
+    vim_nav: {
+        ESC -> TO(base),
+        H -> LEFT,
+        // KeyCode(KC_END) -> [Exit, Exit] can't be expressed in remap!
+    },
+    numpad[Active, Device(1)]: {
+        KP1 -> F1,
+    },
+)
+")
+        );
+    }
+}
@@ -0,0 +1,632 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::layer_composer::LayerComposer;
+use qsk_types::layers::Layer;
+
+use crate::errors::{Error, Result};
+use crate::events::{KeyCode, KeyCode::*};
+
+/// Sexp is a single node of the s-expression syntax shared by kanata and kmonad configuration
+/// files, along with the line it started on.
+#[derive(Clone, Debug, PartialEq)]
+enum Sexp {
+    Atom(String, usize),
+    List(Vec<Sexp>, usize),
+}
+
+impl Sexp {
+    fn line(&self) -> usize {
+        match self {
+            Sexp::Atom(_, line) => *line,
+            Sexp::List(_, line) => *line,
+        }
+    }
+
+    fn atom(&self) -> Option<&str> {
+        match self {
+            Sexp::Atom(s, _) => Some(s.as_str()),
+            Sexp::List(_, _) => None,
+        }
+    }
+}
+
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sexp::Atom(s, _) => write!(f, "{}", s),
+            Sexp::List(items, _) => {
+                let items: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+                write!(f, "({})", items.join(" "))
+            }
+        }
+    }
+}
+
+fn parse(src: &str) -> Result<Vec<Sexp>> {
+    let mut stack: Vec<(Vec<Sexp>, usize)> = Vec::new();
+    let mut forms: Vec<Sexp> = Vec::new();
+    let mut line = 1;
+    let mut chars = src.chars().peekable();
+
+    let mut push = |stack: &mut Vec<(Vec<Sexp>, usize)>, sexp: Sexp| match stack.last_mut() {
+        Some((items, _)) => items.push(sexp),
+        None => forms.push(sexp),
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => (),
+            ';' if chars.peek() == Some(&';') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '#' if chars.peek() == Some(&'|') => {
+                let start = line;
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('#') if prev == '|' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            prev = c;
+                        }
+                        None => {
+                            return Err(Error::Parse {
+                                line: start,
+                                message: "unterminated block comment".to_string(),
+                            })
+                        }
+                    }
+                }
+            }
+            '(' => stack.push((Vec::new(), line)),
+            ')' => match stack.pop() {
+                Some((items, start)) => push(&mut stack, Sexp::List(items, start)),
+                None => {
+                    return Err(Error::Parse {
+                        line,
+                        message: "unexpected )".to_string(),
+                    })
+                }
+            },
+            '"' => {
+                let start = line;
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            s.push(c);
+                        }
+                        None => {
+                            return Err(Error::Parse {
+                                line: start,
+                                message: "unterminated string".to_string(),
+                            })
+                        }
+                    }
+                }
+                push(&mut stack, Sexp::Atom(s, start));
+            }
+            c => {
+                let mut s = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                push(&mut stack, Sexp::Atom(s, line));
+            }
+        }
+    }
+
+    match stack.pop() {
+        Some((_, start)) => Err(Error::Parse {
+            line: start,
+            message: "unclosed (".to_string(),
+        }),
+        None => Ok(forms),
+    }
+}
+
+/// A kanata/kmonad form or action that could not be translated into a qsk `ControlCode`.
+#[derive(Debug, PartialEq)]
+pub struct Unsupported {
+    pub line: usize,
+    pub form: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.form, self.reason)
+    }
+}
+
+impl Unsupported {
+    fn new(sexp: &Sexp, reason: &'static str) -> Self {
+        Unsupported {
+            line: sexp.line(),
+            form: sexp.to_string(),
+            reason,
+        }
+    }
+}
+
+pub struct Import {
+    pub composer: LayerComposer,
+    pub unsupported: Vec<Unsupported>,
+}
+
+/// Reads a kanata or kmonad configuration file and translates the common subset of the two
+/// formats into a `LayerComposer`: `defsrc`, `deflayer`, `defalias`, `tap-hold` (and its
+/// variants), `layer-toggle`/`layer-while-held` and `layer-switch`. The first `deflayer` becomes
+/// the active base layer. Tap-hold timeouts are ignored in favor of qsk's own. Anything else is
+/// left unmapped and returned in `Import::unsupported`.
+pub fn import(path: PathBuf) -> Result<Import> {
+    import_str(&fs::read_to_string(path)?)
+}
+
+pub fn import_str(src: &str) -> Result<Import> {
+    let mut unsupported = Vec::new();
+    let mut defsrc: Option<Vec<Option<KeyCode>>> = None;
+    let mut aliases: HashMap<String, Sexp> = HashMap::new();
+    let mut deflayers: Vec<(String, Vec<Sexp>, usize)> = Vec::new();
+
+    for form in parse(src)? {
+        let items = match &form {
+            Sexp::List(items, _) => items,
+            Sexp::Atom(_, _) => {
+                unsupported.push(Unsupported::new(&form, "unexpected top-level atom"));
+                continue;
+            }
+        };
+        let line = form.line();
+        match items.first().and_then(|head| head.atom()) {
+            Some("defsrc") => {
+                if defsrc.is_some() {
+                    return Err(Error::Parse {
+                        line,
+                        message: "more than one defsrc".to_string(),
+                    });
+                }
+                let keys = items[1..]
+                    .iter()
+                    .map(|item| {
+                        let kc = item.atom().and_then(keycode_from_name);
+                        if kc.is_none() {
+                            unsupported.push(Unsupported::new(item, "unknown source key"));
+                        }
+                        kc
+                    })
+                    .collect();
+                defsrc = Some(keys);
+            }
+            Some("defalias") => {
+                if items.len() % 2 != 1 {
+                    return Err(Error::Parse {
+                        line,
+                        message: "defalias needs pairs of names and actions".to_string(),
+                    });
+                }
+                for pair in items[1..].chunks(2) {
+                    match pair[0].atom() {
+                        Some(name) => {
+                            aliases.insert(name.to_string(), pair[1].clone());
+                        }
+                        None => unsupported.push(Unsupported::new(&pair[0], "alias name must be an atom")),
+                    }
+                }
+            }
+            Some("deflayer") => match items.get(1).and_then(|name| name.atom()) {
+                Some(name) => deflayers.push((name.to_string(), items[2..].to_vec(), line)),
+                None => {
+                    return Err(Error::Parse {
+                        line,
+                        message: "deflayer needs a name".to_string(),
+                    })
+                }
+            },
+            Some("defcfg") => unsupported.push(Unsupported {
+                line,
+                form: "defcfg".to_string(),
+                reason: "device and output configuration is given on the qsk command line",
+            }),
+            _ => unsupported.push(Unsupported::new(&form, "unsupported form")),
+        }
+    }
+
+    let defsrc = defsrc.ok_or_else(|| Error::Parse {
+        line: 1,
+        message: "no defsrc found".to_string(),
+    })?;
+    let translator = Translator {
+        aliases: &aliases,
+        layer_names: deflayers.iter().map(|(name, _, _)| name.as_str()).collect(),
+    };
+
+    let mut layers = Vec::with_capacity(deflayers.len());
+    for (index, (name, actions, line)) in deflayers.iter().enumerate() {
+        if actions.len() != defsrc.len() {
+            return Err(Error::Parse {
+                line: *line,
+                message: format!(
+                    "deflayer {} has {} keys but defsrc has {}",
+                    name,
+                    actions.len(),
+                    defsrc.len()
+                ),
+            });
+        }
+
+        let mut map: HashMap<KeyCode, Vec<ControlCode>> = HashMap::new();
+        for (physical, action) in defsrc.iter().zip(actions.iter()) {
+            let physical = match physical {
+                Some(kc) => *kc,
+                None => continue,
+            };
+            match translator.translate(action, 0) {
+                Ok(None) => (),
+                // the base layer falls through to passthrough, so identity maps are redundant
+                Ok(Some(ControlCode::KeyMap(kc))) if index == 0 && kc == physical => (),
+                Ok(Some(cc)) => {
+                    map.insert(physical, vec![cc]);
+                }
+                Err(reason) => unsupported.push(Unsupported::new(action, reason)),
+            }
+        }
+        layers.push(Layer::from_hashmap(name.clone(), map, index == 0));
+    }
+
+    Ok(Import {
+        composer: LayerComposer::from_layers(layers)?,
+        unsupported,
+    })
+}
+
+struct Translator<'a> {
+    aliases: &'a HashMap<String, Sexp>,
+    layer_names: Vec<&'a str>,
+}
+
+// The tap-hold actions of either format with the number of timeouts leading their arguments.
+// kanata's tap-hold takes two and kmonad's one, which is told apart by the number of arguments.
+const TAP_HOLD_ACTIONS: [(&str, usize); 7] = [
+    ("tap-hold", 1),
+    ("tap-hold-press", 2),
+    ("tap-hold-release", 2),
+    ("tap-next", 0),
+    ("tap-next-release", 0),
+    ("tap-hold-next", 1),
+    ("tap-hold-next-release", 1),
+];
+
+fn tap_hold_timeouts(action: &str, args: &[Sexp]) -> Option<usize> {
+    match TAP_HOLD_ACTIONS.iter().find(|(name, _)| *name == action)? {
+        ("tap-hold", _) if args.len() == 4 => Some(2),
+        (_, timeouts) => Some(*timeouts),
+    }
+}
+
+impl<'a> Translator<'a> {
+    /// Translates a single layer action. `Ok(None)` means the position is transparent and should
+    /// fall through to lower layers.
+    fn translate(&self, action: &Sexp, depth: usize) -> std::result::Result<Option<ControlCode>, &'static str> {
+        if depth > 8 {
+            return Err("alias recursion is too deep");
+        }
+
+        let items = match action {
+            Sexp::Atom(a, _) => {
+                return match a.as_str() {
+                    "_" => Ok(None),
+                    "XX" | "✗" | "∅" | "•" => Err("disabling keys is not supported"),
+                    alias if alias.starts_with('@') => match self.aliases.get(&alias[1..]) {
+                        Some(sexp) => self.translate(sexp, depth + 1),
+                        None => Err("undefined alias"),
+                    },
+                    name => keycode_from_name(name)
+                        .map(|kc| Some(ControlCode::KeyMap(kc)))
+                        .ok_or("unknown key"),
+                }
+            }
+            Sexp::List(items, _) => items,
+        };
+
+        let args = &items[1..];
+        match items.first().and_then(|head| head.atom()) {
+            Some("layer-toggle") | Some("layer-while-held") => match args {
+                [name] => Ok(Some(ControlCode::Momentary(self.layer(name)?))),
+                _ => Err("wrong number of arguments"),
+            },
+            Some("layer-switch") => match args {
+                [name] => Ok(Some(ControlCode::Switch(self.layer(name)?))),
+                _ => Err("wrong number of arguments"),
+            },
+            Some(th) if tap_hold_timeouts(th, args).is_some() => {
+                // qsk uses its own timeout rather than those given
+                let timeouts = tap_hold_timeouts(th, args).unwrap_or(0).min(args.len());
+                let (timeouts, args) = args.split_at(timeouts);
+                if !timeouts.iter().all(|t| t.atom().is_some_and(|a| a.parse::<u32>().is_ok())) {
+                    return Err("timeout must be a number");
+                }
+                let (tap, hold) = match args {
+                    [tap, hold] => (
+                        self.translate(tap, depth + 1)?,
+                        self.translate(hold, depth + 1)?,
+                    ),
+                    _ => return Err("wrong number of arguments"),
+                };
+                match (tap, hold) {
+                    (Some(ControlCode::KeyMap(kc)), Some(ControlCode::KeyMap(modifier))) => {
+                        Ok(Some(ControlCode::ModTap(modifier, kc)))
+                    }
+                    (Some(ControlCode::KeyMap(kc)), Some(ControlCode::Momentary(lr))) => {
+                        Ok(Some(ControlCode::TapToggle(lr, kc)))
+                    }
+                    (Some(ControlCode::KeyMap(_)), _) => Err("hold action must be a key or layer-toggle"),
+                    _ => Err("tap action must be a key"),
+                }
+            }
+            _ => Err("unsupported action"),
+        }
+    }
+
+    fn layer(&self, name: &Sexp) -> std::result::Result<LayerRef, &'static str> {
+        match name.atom() {
+            Some(n) if self.layer_names.contains(&n) => Ok(LayerRef::ByName(n.to_string())),
+            Some(_) => Err("layer does not exist"),
+            None => Err("layer name must be an atom"),
+        }
+    }
+}
+
+/// Translates a kanata/kmonad key name into a `KeyCode`. Names that aren't abbreviations, such as
+/// `a`, `f1` or `home`, are looked up directly as qsk key codes.
+pub fn keycode_from_name(name: &str) -> Option<KeyCode> {
+    match NAMES.iter().find(|(n, _)| *n == name) {
+        Some((_, kc)) => Some(*kc),
+        None => KeyCode::from_str(&format!("KC_{}", name.to_uppercase())).ok(),
+    }
+}
+
+const NAMES: [(&str, KeyCode); 79] = [
+    ("grv", KC_GRAVE),
+    ("`", KC_GRAVE),
+    ("min", KC_MINUS),
+    ("mins", KC_MINUS),
+    ("-", KC_MINUS),
+    ("eql", KC_EQUAL),
+    ("=", KC_EQUAL),
+    ("bspc", KC_BACKSPACE),
+    ("bks", KC_BACKSPACE),
+    ("bck", KC_BACKSPACE),
+    ("lbrc", KC_LEFTBRACE),
+    ("[", KC_LEFTBRACE),
+    ("rbrc", KC_RIGHTBRACE),
+    ("]", KC_RIGHTBRACE),
+    ("bksl", KC_BACKSLASH),
+    ("\\", KC_BACKSLASH),
+    ("caps", KC_CAPSLOCK),
+    ("scln", KC_SEMICOLON),
+    (";", KC_SEMICOLON),
+    ("apos", KC_APOSTROPHE),
+    ("'", KC_APOSTROPHE),
+    ("ret", KC_ENTER),
+    ("ent", KC_ENTER),
+    ("return", KC_ENTER),
+    ("comm", KC_COMMA),
+    (",", KC_COMMA),
+    (".", KC_DOT),
+    ("/", KC_SLASH),
+    ("spc", KC_SPACE),
+    ("lsft", KC_LEFTSHIFT),
+    ("lshift", KC_LEFTSHIFT),
+    ("rsft", KC_RIGHTSHIFT),
+    ("rshift", KC_RIGHTSHIFT),
+    ("lctl", KC_LEFTCTRL),
+    ("lctrl", KC_LEFTCTRL),
+    ("rctl", KC_RIGHTCTRL),
+    ("rctrl", KC_RIGHTCTRL),
+    ("lalt", KC_LEFTALT),
+    ("ralt", KC_RIGHTALT),
+    ("lmet", KC_LEFTMETA),
+    ("lmeta", KC_LEFTMETA),
+    ("lgui", KC_LEFTMETA),
+    ("lwin", KC_LEFTMETA),
+    ("rmet", KC_RIGHTMETA),
+    ("rmeta", KC_RIGHTMETA),
+    ("rgui", KC_RIGHTMETA),
+    ("rwin", KC_RIGHTMETA),
+    ("cmp", KC_COMPOSE),
+    ("comp", KC_COMPOSE),
+    ("menu", KC_COMPOSE),
+    ("prnt", KC_SYSRQ),
+    ("prtsc", KC_SYSRQ),
+    ("ssrq", KC_SYSRQ),
+    ("slck", KC_SCROLLLOCK),
+    ("scrlck", KC_SCROLLLOCK),
+    ("pau", KC_PAUSE),
+    ("ins", KC_INSERT),
+    ("del", KC_DELETE),
+    ("pgup", KC_PAGEUP),
+    ("pgdn", KC_PAGEDOWN),
+    ("rght", KC_RIGHT),
+    ("nlck", KC_NUMLOCK),
+    ("nlk", KC_NUMLOCK),
+    ("kp/", KC_KPSLASH),
+    ("kp*", KC_KPASTERISK),
+    ("kp-", KC_KPMINUS),
+    ("kp+", KC_KPPLUS),
+    ("kprt", KC_KPENTER),
+    ("kp.", KC_KPDOT),
+    ("kp=", KC_KPEQUAL),
+    ("volu", KC_VOLUMEUP),
+    ("vold", KC_VOLUMEDOWN),
+    ("voldwn", KC_VOLUMEDOWN),
+    ("pp", KC_PLAYPAUSE),
+    ("next", KC_NEXTSONG),
+    ("prev", KC_PREVIOUSSONG),
+    ("nubs", KC_102ND),
+    ("102d", KC_102ND),
+    ("⎋", KC_ESC),
+];
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+    use crate::events::EventCode;
+
+    const CONFIG: &str = r#"
+;; a comment with (parens
+(defcfg
+  input (device-file "/dev/input/by-id/usb-keyboard")
+  output (uinput-sink "qsk"))
+
+#| block comment
+   (deflayer ignored) |#
+(defsrc
+  caps a    s    d    f    h    j    k    l    ;    esc)
+
+(defalias
+  nav (layer-toggle nav)
+  a_ctl (tap-hold 200 200 a lctl)
+  f_nav (tap-hold 200 @f_nav_hold)
+  f_nav_hold f)
+
+(deflayer base
+  esc  @a_ctl s  d    (tap-hold 180 f @nav) h j k l ; (layer-switch nav))
+
+(deflayer nav
+  _    _    _    _    _    left down up rght XX (layer-switch base))
+
+(defchords foo 50)
+"#;
+
+    fn map(layer: &Layer) -> HashMap<EventCode, Vec<ControlCode>> {
+        layer.iter().map(|(k, v)| (EventCode::KeyCode(k), v.clone())).collect()
+    }
+
+    #[test]
+    fn parse_sexps() {
+        let forms = parse("(a (b \"c d\") ;)\n;; (e)\n(f)").unwrap();
+        assert_that!(&forms.len(), eq(2));
+        assert_that!(&forms[0].to_string(), eq("(a (b c d) ;)".to_string()));
+        assert_that!(&forms[1].line(), eq(3));
+
+        assert!(matches!(parse("(a\n(b)"), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(parse("(a))"), Err(Error::Parse { line: 1, .. })));
+    }
+
+    #[test]
+    fn import_config() {
+        let import = import_str(CONFIG).unwrap();
+        let layers: Vec<&Layer> = import.composer.iter().collect();
+        assert_that!(&layers.len(), eq(2));
+        assert_that!(&layers[0].name.as_str(), eq("base"));
+        assert_that!(&layers[0].active, eq(true));
+        assert_that!(&layers[1].active, eq(false));
+
+        let base = map(layers[0]);
+        assert_that!(&base.len(), eq(4));
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_CAPSLOCK)],
+            eq(vec![ControlCode::KeyMap(KC_ESC)])
+        );
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_A)],
+            eq(vec![ControlCode::ModTap(KC_LEFTCTRL, KC_A)])
+        );
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_F)],
+            eq(vec![ControlCode::TapToggle(LayerRef::ByName("nav".to_string()), KC_F)])
+        );
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_ESC)],
+            eq(vec![ControlCode::Switch(LayerRef::ByName("nav".to_string()))])
+        );
+
+        let nav = map(layers[1]);
+        assert_that!(&nav.len(), eq(5));
+        assert_that!(&nav[&EventCode::KeyCode(KC_L)], eq(vec![ControlCode::KeyMap(KC_RIGHT)]));
+
+        let reasons: Vec<(usize, &str)> = import
+            .unsupported
+            .iter()
+            .map(|u| (u.line, u.reason))
+            .collect();
+        assert_that!(
+            &reasons,
+            eq(vec![
+                (3, "device and output configuration is given on the qsk command line"),
+                (24, "unsupported form"),
+                (22, "disabling keys is not supported"),
+            ])
+        );
+    }
+
+    #[test]
+    fn import_unsupported_actions() {
+        let import = import_str(
+            "(defsrc a b c d)\n(deflayer base (tap-hold 200 200 (layer-toggle base) a) (multi a b) @nope (layer-toggle nope))",
+        )
+        .unwrap();
+        let reasons: Vec<&str> = import.unsupported.iter().map(|u| u.reason).collect();
+        assert_that!(
+            &reasons,
+            eq(vec![
+                "tap action must be a key",
+                "unsupported action",
+                "undefined alias",
+                "layer does not exist",
+            ])
+        );
+    }
+
+    #[test]
+    fn import_digit_tap_keys() {
+        let import = import_str(
+            "(defsrc a b c)\n(deflayer base (tap-hold 200 200 1 lctl) (tap-hold 200 1 2) (tap-next 3 lsft))",
+        )
+        .unwrap();
+        assert_that!(&import.unsupported.len(), eq(0));
+        let base = map(import.composer.iter().next().unwrap());
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_A)],
+            eq(vec![ControlCode::ModTap(KC_LEFTCTRL, KC_1)])
+        );
+        assert_that!(&base[&EventCode::KeyCode(KC_B)], eq(vec![ControlCode::ModTap(KC_2, KC_1)]));
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_C)],
+            eq(vec![ControlCode::ModTap(KC_LEFTSHIFT, KC_3)])
+        );
+    }
+
+    #[test]
+    fn import_length_mismatch() {
+        assert!(matches!(
+            import_str("(defsrc a b)\n(deflayer base a)"),
+            Err(Error::Parse { line: 2, .. })
+        ));
+    }
+}
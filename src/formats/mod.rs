@@ -0,0 +1,3 @@
+pub mod dsl;
+pub mod kanata;
+pub mod qmk;
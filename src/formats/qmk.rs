@@ -0,0 +1,667 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::layer_composer::LayerComposer;
+use qsk_types::layers::Layer;
+
+use crate::errors::{Error, Result};
+use crate::events::{KeyCode, KeyCode::*};
+
+/// Keymap is the subset of QMK's `keymap.json` format that qsk understands.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Keymap {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keymap: Option<String>,
+    pub layout: String,
+    pub layers: Vec<Vec<String>>,
+}
+
+impl Keymap {
+    pub fn from_path(path: PathBuf) -> Result<Keymap> {
+        let f = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(f))?)
+    }
+
+    pub fn write<W: Write>(&self, w: W) -> Result<()> {
+        Ok(serde_json::to_writer_pretty(w, self)?)
+    }
+}
+
+/// A QMK keycode that could not be translated into a qsk `ControlCode`.
+#[derive(Debug, PartialEq)]
+pub struct Unsupported {
+    pub layer: usize,
+    pub position: usize,
+    pub keycode: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "layer {} position {}: {} ({})",
+            self.layer, self.position, self.keycode, self.reason
+        )
+    }
+}
+
+pub struct Import {
+    pub composer: LayerComposer,
+    pub unsupported: Vec<Unsupported>,
+}
+
+/// Reads a QMK `keymap.json` file and translates it into a `LayerComposer`. Keycodes that have no
+/// qsk equivalent are left unmapped and returned in `Import::unsupported`.
+pub fn import(path: PathBuf) -> Result<Import> {
+    import_keymap(&Keymap::from_path(path)?)
+}
+
+pub fn import_keymap(keymap: &Keymap) -> Result<Import> {
+    let positions = layout(&keymap.layout)
+        .ok_or_else(|| Error::UnknownLayout(keymap.layout.clone()))?;
+
+    let mut unsupported = Vec::new();
+    let mut layers = Vec::with_capacity(keymap.layers.len());
+    for (index, keycodes) in keymap.layers.iter().enumerate() {
+        if keycodes.len() != positions.len() {
+            return Err(Error::LayoutMismatch {
+                layout: keymap.layout.clone(),
+                layer: index,
+                expected: positions.len(),
+                found: keycodes.len(),
+            });
+        }
+
+        let mut map: HashMap<KeyCode, Vec<ControlCode>> = HashMap::new();
+        for (position, (physical, keycode)) in positions.iter().zip(keycodes.iter()).enumerate() {
+            match translate(keycode, keymap.layers.len()) {
+                Ok(None) => (),
+                // the bottom layer falls through to passthrough, so identity maps are redundant
+                Ok(Some(ControlCode::KeyMap(kc))) if index == 0 && kc == *physical => (),
+                Ok(Some(cc)) => {
+                    map.insert(*physical, vec![cc]);
+                }
+                Err(reason) => unsupported.push(Unsupported {
+                    layer: index,
+                    position,
+                    keycode: keycode.clone(),
+                    reason,
+                }),
+            }
+        }
+        layers.push(Layer::from_hashmap(format!("layer{}", index), map, index == 0));
+    }
+
+    Ok(Import {
+        composer: LayerComposer::from_layers(layers)?,
+        unsupported,
+    })
+}
+
+/// A part of a `LayerComposer` that could not be represented in QMK's keymap format.
+#[derive(Debug, PartialEq)]
+pub struct Warning {
+    pub layer: String,
+    pub key: Option<KeyCode>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.key {
+            Some(kc) => write!(f, "layer {} key {}: {}", self.layer, kc, self.message),
+            None => write!(f, "layer {}: {}", self.layer, self.message),
+        }
+    }
+}
+
+pub struct Export {
+    pub keymap: Keymap,
+    pub warnings: Vec<Warning>,
+}
+
+/// Translates a `LayerComposer` into a QMK keymap for the named layout. Keys that are unmapped in
+/// a layer become `KC_TRNS`, except on the bottom layer where they send their own key code. Any
+/// mapping that QMK can't express is replaced by `KC_NO` and reported in `Export::warnings`.
+pub fn export(lc: &LayerComposer, layout_name: &str) -> Result<Export> {
+    let positions = layout(layout_name)
+        .ok_or_else(|| Error::UnknownLayout(layout_name.to_string()))?;
+    let names: Vec<&str> = lc.iter().map(|layer| layer.name.as_str()).collect();
+
+    let mut warnings = Vec::new();
+    let mut layers = Vec::with_capacity(names.len());
+    for (index, layer) in lc.iter().enumerate() {
+        let mut warn = |key: Option<KeyCode>, message: String| {
+            warnings.push(Warning {
+                layer: layer.name.clone(),
+                key,
+                message,
+            })
+        };
+        if index > 0 && layer.active {
+            warn(None, "only the bottom layer is active at startup in QMK".to_string());
+        }
+
+        let mut map: HashMap<KeyCode, &Vec<ControlCode>> = HashMap::new();
+        for (kc, ccs) in layer.iter() {
+            match positions.contains(&kc) {
+                true => {
+                    map.insert(kc, ccs);
+                }
+                false => warn(Some(kc), format!("key is not part of {}", layout_name)),
+            }
+        }
+
+        let keycodes = positions
+            .iter()
+            .map(|physical| match (map.get(physical), index) {
+                (None, 0) => keycode_to_qmk(*physical),
+                (None, _) => "KC_TRNS".to_string(),
+                (Some(ccs), _) => {
+                    if ccs.len() > 1 {
+                        warn(Some(*physical), "only the first of several control codes is exported".to_string());
+                    }
+                    match ccs.first().map(|cc| control_code_to_qmk(cc, &names)) {
+                        Some(Ok(keycode)) => keycode,
+                        Some(Err(message)) => {
+                            warn(Some(*physical), message);
+                            "KC_NO".to_string()
+                        }
+                        None => "KC_TRNS".to_string(),
+                    }
+                }
+            })
+            .collect();
+        layers.push(keycodes);
+    }
+
+    Ok(Export {
+        keymap: Keymap {
+            layout: layout_name.to_string(),
+            layers,
+            ..Keymap::default()
+        },
+        warnings,
+    })
+}
+
+fn control_code_to_qmk(cc: &ControlCode, names: &[&str]) -> std::result::Result<String, String> {
+    let layer = |lr: &LayerRef| match lr {
+        LayerRef::ByIndex(i) => Ok(*i),
+        LayerRef::ByName(name) => names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| format!("layer {} does not exist", name)),
+    };
+    match cc {
+        ControlCode::KeyMap(kc) => Ok(keycode_to_qmk(*kc)),
+        ControlCode::TapToggle(lr, kc) => Ok(format!("LT({}, {})", layer(lr)?, keycode_to_qmk(*kc))),
+        ControlCode::Momentary(lr) => Ok(format!("MO({})", layer(lr)?)),
+        ControlCode::Toggle(lr) => Ok(format!("TG({})", layer(lr)?)),
+        ControlCode::Switch(lr) => Ok(format!("TO({})", layer(lr)?)),
+        ControlCode::ModTap(modifier, kc) => match MODIFIERS.iter().find(|(_, m)| m == modifier) {
+            Some((name, _)) => Ok(format!("MT({}, {})", name, keycode_to_qmk(*kc))),
+            None => Err(format!("{} is not a modifier", modifier)),
+        },
+        ControlCode::MouseMove(..) | ControlCode::MouseWheel(_) | ControlCode::MouseButton(_) => {
+            mouse_to_qmk(cc).ok_or_else(|| format!("{:?} has no QMK equivalent", cc))
+        }
+        _ => Err(format!("{:?} has no QMK equivalent", cc)),
+    }
+}
+
+/// Translates a single QMK keycode. `Ok(None)` means the position is transparent and should fall
+/// through to lower layers.
+fn translate(keycode: &str, layer_count: usize) -> std::result::Result<Option<ControlCode>, &'static str> {
+    let keycode = keycode.trim();
+    match keycode {
+        "KC_TRNS" | "KC_TRANSPARENT" | "_______" => return Ok(None),
+        "KC_NO" | "XXXXXXX" => return Err("disabling keys is not supported"),
+        _ => (),
+    }
+
+    let (name, args) = match (keycode.find('('), keycode.ends_with(')')) {
+        (Some(open), true) => (
+            &keycode[..open],
+            keycode[open + 1..keycode.len() - 1]
+                .split(',')
+                .map(|arg| arg.trim())
+                .collect::<Vec<&str>>(),
+        ),
+        (None, _) if mouse_from_qmk(keycode).is_some() => return Ok(mouse_from_qmk(keycode)),
+        (None, _) => return keycode_from_qmk(keycode)
+            .map(|kc| Some(ControlCode::KeyMap(kc)))
+            .ok_or("unknown keycode"),
+        _ => return Err("malformed keycode"),
+    };
+
+    let layer = |arg: &str| -> std::result::Result<LayerRef, &'static str> {
+        match arg.parse::<usize>() {
+            Ok(i) if i < layer_count => Ok(LayerRef::ByIndex(i)),
+            Ok(_) => Err("layer index out of range"),
+            Err(_) => Err("layer argument must be a number"),
+        }
+    };
+    let key = |arg: &str| keycode_from_qmk(arg).ok_or("unknown keycode argument");
+
+    match (name, &args[..]) {
+        ("MO", [l]) => Ok(Some(ControlCode::Momentary(layer(l)?))),
+        ("TG", [l]) => Ok(Some(ControlCode::Toggle(layer(l)?))),
+        ("TO", [l]) => Ok(Some(ControlCode::Switch(layer(l)?))),
+        ("LT", [l, k]) => Ok(Some(ControlCode::TapToggle(layer(l)?, key(k)?))),
+        ("MT", [m, k]) => Ok(Some(ControlCode::ModTap(modifier_from_qmk(m)?, key(k)?))),
+        ("LCTL_T", [k]) => Ok(Some(ControlCode::ModTap(KC_LEFTCTRL, key(k)?))),
+        ("LSFT_T", [k]) => Ok(Some(ControlCode::ModTap(KC_LEFTSHIFT, key(k)?))),
+        ("LALT_T", [k]) => Ok(Some(ControlCode::ModTap(KC_LEFTALT, key(k)?))),
+        ("LGUI_T", [k]) => Ok(Some(ControlCode::ModTap(KC_LEFTMETA, key(k)?))),
+        ("RCTL_T", [k]) => Ok(Some(ControlCode::ModTap(KC_RIGHTCTRL, key(k)?))),
+        ("RSFT_T", [k]) => Ok(Some(ControlCode::ModTap(KC_RIGHTSHIFT, key(k)?))),
+        ("RALT_T", [k]) => Ok(Some(ControlCode::ModTap(KC_RIGHTALT, key(k)?))),
+        ("RGUI_T", [k]) => Ok(Some(ControlCode::ModTap(KC_RIGHTMETA, key(k)?))),
+        ("MO", _) | ("TG", _) | ("TO", _) | ("LT", _) | ("MT", _) => Err("wrong number of arguments"),
+        _ => Err("unsupported key function"),
+    }
+}
+
+fn modifier_from_qmk(m: &str) -> std::result::Result<KeyCode, &'static str> {
+    MODIFIERS
+        .iter()
+        .find(|(name, _)| *name == m)
+        .map(|(_, kc)| *kc)
+        .ok_or("only single-modifier mod-taps are supported")
+}
+
+const MODIFIERS: [(&str, KeyCode); 8] = [
+    ("MOD_LCTL", KC_LEFTCTRL),
+    ("MOD_LSFT", KC_LEFTSHIFT),
+    ("MOD_LALT", KC_LEFTALT),
+    ("MOD_LGUI", KC_LEFTMETA),
+    ("MOD_RCTL", KC_RIGHTCTRL),
+    ("MOD_RSFT", KC_RIGHTSHIFT),
+    ("MOD_RALT", KC_RIGHTALT),
+    ("MOD_RGUI", KC_RIGHTMETA),
+];
+
+// QMK's default MOUSEKEY_MOVE_DELTA
+const MOUSE_STEP: i32 = 8;
+
+// Mouse keys, canonical names first.
+const MOUSE_KEYS: [(&str, ControlCode); 22] = [
+    ("KC_MS_U", ControlCode::MouseMove(0, -MOUSE_STEP)),
+    ("KC_MS_D", ControlCode::MouseMove(0, MOUSE_STEP)),
+    ("KC_MS_L", ControlCode::MouseMove(-MOUSE_STEP, 0)),
+    ("KC_MS_R", ControlCode::MouseMove(MOUSE_STEP, 0)),
+    ("KC_BTN1", ControlCode::MouseButton(KC_BTN_LEFT)),
+    ("KC_BTN2", ControlCode::MouseButton(KC_BTN_RIGHT)),
+    ("KC_BTN3", ControlCode::MouseButton(KC_BTN_MIDDLE)),
+    ("KC_BTN4", ControlCode::MouseButton(KC_BTN_SIDE)),
+    ("KC_BTN5", ControlCode::MouseButton(KC_BTN_EXTRA)),
+    ("KC_WH_U", ControlCode::MouseWheel(1)),
+    ("KC_WH_D", ControlCode::MouseWheel(-1)),
+    ("KC_MS_UP", ControlCode::MouseMove(0, -MOUSE_STEP)),
+    ("KC_MS_DOWN", ControlCode::MouseMove(0, MOUSE_STEP)),
+    ("KC_MS_LEFT", ControlCode::MouseMove(-MOUSE_STEP, 0)),
+    ("KC_MS_RIGHT", ControlCode::MouseMove(MOUSE_STEP, 0)),
+    ("KC_MS_BTN1", ControlCode::MouseButton(KC_BTN_LEFT)),
+    ("KC_MS_BTN2", ControlCode::MouseButton(KC_BTN_RIGHT)),
+    ("KC_MS_BTN3", ControlCode::MouseButton(KC_BTN_MIDDLE)),
+    ("KC_MS_BTN4", ControlCode::MouseButton(KC_BTN_SIDE)),
+    ("KC_MS_BTN5", ControlCode::MouseButton(KC_BTN_EXTRA)),
+    ("KC_MS_WH_UP", ControlCode::MouseWheel(1)),
+    ("KC_MS_WH_DOWN", ControlCode::MouseWheel(-1)),
+];
+
+fn mouse_from_qmk(name: &str) -> Option<ControlCode> {
+    MOUSE_KEYS
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, cc)| cc.clone())
+}
+
+// QMK's mouse keys have fixed speeds, so any movement along a single axis is exported as the key
+// for that direction.
+fn mouse_to_qmk(cc: &ControlCode) -> Option<String> {
+    let normalized = match cc {
+        ControlCode::MouseMove(dx, 0) if *dx != 0 => ControlCode::MouseMove(dx.signum() * MOUSE_STEP, 0),
+        ControlCode::MouseMove(0, dy) if *dy != 0 => ControlCode::MouseMove(0, dy.signum() * MOUSE_STEP),
+        ControlCode::MouseWheel(n) if *n != 0 => ControlCode::MouseWheel(n.signum()),
+        _ => cc.clone(),
+    };
+    MOUSE_KEYS
+        .iter()
+        .find(|(_, mouse)| *mouse == normalized)
+        .map(|(name, _)| name.to_string())
+}
+
+/// Translates a basic QMK keycode into a `KeyCode`, falling back to qsk's own naming since most
+/// QMK keycodes share their name with the Linux key code.
+pub fn keycode_from_qmk(name: &str) -> Option<KeyCode> {
+    match ALIASES.iter().find(|(alias, _)| *alias == name) {
+        Some((_, kc)) => Some(*kc),
+        None => KeyCode::from_str(name).ok(),
+    }
+}
+
+/// Translates a `KeyCode` into its canonical QMK keycode name.
+pub fn keycode_to_qmk(kc: KeyCode) -> String {
+    match ALIASES.iter().find(|(_, aliased)| *aliased == kc) {
+        Some((alias, _)) => alias.to_string(),
+        None => kc.to_string(),
+    }
+}
+
+/// QMK keycodes whose names differ from qsk's `KeyCode` variant names. Where several QMK names map
+/// to the same key the canonical short name comes first.
+const ALIASES: [(&str, KeyCode); 85] = [
+    ("KC_ENT", KC_ENTER),
+    ("KC_BSPC", KC_BACKSPACE),
+    ("KC_SPC", KC_SPACE),
+    ("KC_MINS", KC_MINUS),
+    ("KC_EQL", KC_EQUAL),
+    ("KC_LBRC", KC_LEFTBRACE),
+    ("KC_RBRC", KC_RIGHTBRACE),
+    ("KC_BSLS", KC_BACKSLASH),
+    ("KC_NUBS", KC_102ND),
+    ("KC_SCLN", KC_SEMICOLON),
+    ("KC_QUOT", KC_APOSTROPHE),
+    ("KC_GRV", KC_GRAVE),
+    ("KC_COMM", KC_COMMA),
+    ("KC_SLSH", KC_SLASH),
+    ("KC_CAPS", KC_CAPSLOCK),
+    ("KC_PSCR", KC_SYSRQ),
+    ("KC_SCRL", KC_SCROLLLOCK),
+    ("KC_PAUS", KC_PAUSE),
+    ("KC_INS", KC_INSERT),
+    ("KC_DEL", KC_DELETE),
+    ("KC_PGUP", KC_PAGEUP),
+    ("KC_PGDN", KC_PAGEDOWN),
+    ("KC_RGHT", KC_RIGHT),
+    ("KC_APP", KC_COMPOSE),
+    ("KC_NUM", KC_NUMLOCK),
+    ("KC_PSLS", KC_KPSLASH),
+    ("KC_PAST", KC_KPASTERISK),
+    ("KC_PMNS", KC_KPMINUS),
+    ("KC_PPLS", KC_KPPLUS),
+    ("KC_PENT", KC_KPENTER),
+    ("KC_PDOT", KC_KPDOT),
+    ("KC_PEQL", KC_KPEQUAL),
+    ("KC_PCMM", KC_KPCOMMA),
+    ("KC_P1", KC_KP1),
+    ("KC_P2", KC_KP2),
+    ("KC_P3", KC_KP3),
+    ("KC_P4", KC_KP4),
+    ("KC_P5", KC_KP5),
+    ("KC_P6", KC_KP6),
+    ("KC_P7", KC_KP7),
+    ("KC_P8", KC_KP8),
+    ("KC_P9", KC_KP9),
+    ("KC_P0", KC_KP0),
+    ("KC_LCTL", KC_LEFTCTRL),
+    ("KC_LSFT", KC_LEFTSHIFT),
+    ("KC_LALT", KC_LEFTALT),
+    ("KC_LGUI", KC_LEFTMETA),
+    ("KC_RCTL", KC_RIGHTCTRL),
+    ("KC_RSFT", KC_RIGHTSHIFT),
+    ("KC_RALT", KC_RIGHTALT),
+    ("KC_RGUI", KC_RIGHTMETA),
+    ("KC_VOLU", KC_VOLUMEUP),
+    ("KC_VOLD", KC_VOLUMEDOWN),
+    ("KC_MPLY", KC_PLAYPAUSE),
+    ("KC_MNXT", KC_NEXTSONG),
+    ("KC_MPRV", KC_PREVIOUSSONG),
+    ("KC_MSTP", KC_STOPCD),
+    // long-form and legacy names
+    ("KC_LEFT_BRACKET", KC_LEFTBRACE),
+    ("KC_RIGHT_BRACKET", KC_RIGHTBRACE),
+    ("KC_QUOTE", KC_APOSTROPHE),
+    ("KC_CAPS_LOCK", KC_CAPSLOCK),
+    ("KC_PRINT_SCREEN", KC_SYSRQ),
+    ("KC_SCROLL_LOCK", KC_SCROLLLOCK),
+    ("KC_SLCK", KC_SCROLLLOCK),
+    ("KC_PAGE_UP", KC_PAGEUP),
+    ("KC_PAGE_DOWN", KC_PAGEDOWN),
+    ("KC_NUM_LOCK", KC_NUMLOCK),
+    ("KC_NLCK", KC_NUMLOCK),
+    ("KC_APPLICATION", KC_COMPOSE),
+    ("KC_LEFT_CTRL", KC_LEFTCTRL),
+    ("KC_LCTRL", KC_LEFTCTRL),
+    ("KC_LEFT_SHIFT", KC_LEFTSHIFT),
+    ("KC_LSHIFT", KC_LEFTSHIFT),
+    ("KC_LEFT_ALT", KC_LEFTALT),
+    ("KC_LEFT_GUI", KC_LEFTMETA),
+    ("KC_RIGHT_CTRL", KC_RIGHTCTRL),
+    ("KC_RCTRL", KC_RIGHTCTRL),
+    ("KC_RIGHT_SHIFT", KC_RIGHTSHIFT),
+    ("KC_RSHIFT", KC_RIGHTSHIFT),
+    ("KC_RIGHT_ALT", KC_RIGHTALT),
+    ("KC_ALGR", KC_RIGHTALT),
+    ("KC_RIGHT_GUI", KC_RIGHTMETA),
+    ("KC_AUDIO_MUTE", KC_MUTE),
+    ("KC_AUDIO_VOL_UP", KC_VOLUMEUP),
+    ("KC_AUDIO_VOL_DOWN", KC_VOLUMEDOWN),
+];
+
+/// Returns the Linux key codes sent by the physical keys at each position of the named QMK
+/// layout, in the same order QMK lists them. Positions without a dedicated key on a standard
+/// laptop keyboard are mapped to the key that sits in the same place there; for example the
+/// top-left key of a 60% board is `KC_GRAVE`.
+pub fn layout(name: &str) -> Option<&'static [KeyCode]> {
+    match name {
+        "LAYOUT_60_ansi" => Some(&LAYOUT_60_ANSI),
+        "LAYOUT_tkl_ansi" => Some(&LAYOUT_TKL_ANSI),
+        _ => None,
+    }
+}
+
+pub const LAYOUTS: [&str; 2] = ["LAYOUT_60_ansi", "LAYOUT_tkl_ansi"];
+
+const LAYOUT_60_ANSI: [KeyCode; 61] = [
+    KC_GRAVE, KC_1, KC_2, KC_3, KC_4, KC_5, KC_6, KC_7, KC_8, KC_9, KC_0, KC_MINUS, KC_EQUAL, KC_BACKSPACE,
+    KC_TAB, KC_Q, KC_W, KC_E, KC_R, KC_T, KC_Y, KC_U, KC_I, KC_O, KC_P, KC_LEFTBRACE, KC_RIGHTBRACE, KC_BACKSLASH,
+    KC_CAPSLOCK, KC_A, KC_S, KC_D, KC_F, KC_G, KC_H, KC_J, KC_K, KC_L, KC_SEMICOLON, KC_APOSTROPHE, KC_ENTER,
+    KC_LEFTSHIFT, KC_Z, KC_X, KC_C, KC_V, KC_B, KC_N, KC_M, KC_COMMA, KC_DOT, KC_SLASH, KC_RIGHTSHIFT,
+    KC_LEFTCTRL, KC_LEFTMETA, KC_LEFTALT, KC_SPACE, KC_RIGHTALT, KC_RIGHTMETA, KC_COMPOSE, KC_RIGHTCTRL,
+];
+
+const LAYOUT_TKL_ANSI: [KeyCode; 87] = [
+    KC_ESC, KC_F1, KC_F2, KC_F3, KC_F4, KC_F5, KC_F6, KC_F7, KC_F8, KC_F9, KC_F10, KC_F11, KC_F12,
+    KC_SYSRQ, KC_SCROLLLOCK, KC_PAUSE,
+    KC_GRAVE, KC_1, KC_2, KC_3, KC_4, KC_5, KC_6, KC_7, KC_8, KC_9, KC_0, KC_MINUS, KC_EQUAL, KC_BACKSPACE,
+    KC_INSERT, KC_HOME, KC_PAGEUP,
+    KC_TAB, KC_Q, KC_W, KC_E, KC_R, KC_T, KC_Y, KC_U, KC_I, KC_O, KC_P, KC_LEFTBRACE, KC_RIGHTBRACE, KC_BACKSLASH,
+    KC_DELETE, KC_END, KC_PAGEDOWN,
+    KC_CAPSLOCK, KC_A, KC_S, KC_D, KC_F, KC_G, KC_H, KC_J, KC_K, KC_L, KC_SEMICOLON, KC_APOSTROPHE, KC_ENTER,
+    KC_LEFTSHIFT, KC_Z, KC_X, KC_C, KC_V, KC_B, KC_N, KC_M, KC_COMMA, KC_DOT, KC_SLASH, KC_RIGHTSHIFT,
+    KC_UP,
+    KC_LEFTCTRL, KC_LEFTMETA, KC_LEFTALT, KC_SPACE, KC_RIGHTALT, KC_RIGHTMETA, KC_COMPOSE, KC_RIGHTCTRL,
+    KC_LEFT, KC_DOWN, KC_RIGHT,
+];
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+    use crate::events::EventCode;
+
+    fn keymap(layers: Vec<Vec<&str>>) -> Keymap {
+        Keymap {
+            layout: "LAYOUT_60_ansi".to_string(),
+            layers: layers
+                .into_iter()
+                .map(|l| l.into_iter().map(String::from).collect())
+                .collect(),
+            ..Keymap::default()
+        }
+    }
+
+    fn layer_with(pairs: Vec<(usize, &'static str)>) -> Vec<&'static str> {
+        let mut layer = vec!["KC_TRNS"; LAYOUT_60_ANSI.len()];
+        for (position, kc) in pairs {
+            layer[position] = kc;
+        }
+        layer
+    }
+
+    #[test]
+    fn translate_basic_keycodes() {
+        assert_that!(&translate("KC_A", 1), eq(Ok(Some(ControlCode::KeyMap(KC_A)))));
+        assert_that!(&translate("KC_BSPC", 1), eq(Ok(Some(ControlCode::KeyMap(KC_BACKSPACE)))));
+        assert_that!(&translate("KC_LCTRL", 1), eq(Ok(Some(ControlCode::KeyMap(KC_LEFTCTRL)))));
+        assert_that!(&translate("_______", 1), eq(Ok(None)));
+        assert_that!(&translate("KC_NOPE", 1), eq(Err("unknown keycode")));
+    }
+
+    #[test]
+    fn translate_mouse_keys() {
+        assert_that!(&translate("KC_MS_U", 1), eq(Ok(Some(ControlCode::MouseMove(0, -8)))));
+        assert_that!(&translate("KC_MS_BTN1", 1), eq(Ok(Some(ControlCode::MouseButton(KC_BTN_LEFT)))));
+        assert_that!(&translate("KC_WH_D", 1), eq(Ok(Some(ControlCode::MouseWheel(-1)))));
+
+        assert_that!(&mouse_to_qmk(&ControlCode::MouseMove(-3, 0)), eq(Some("KC_MS_L".to_string())));
+        assert_that!(&mouse_to_qmk(&ControlCode::MouseWheel(2)), eq(Some("KC_WH_U".to_string())));
+        assert_that!(&mouse_to_qmk(&ControlCode::MouseButton(KC_BTN_EXTRA)), eq(Some("KC_BTN5".to_string())));
+        assert_that!(&mouse_to_qmk(&ControlCode::MouseMove(3, 3)), eq(None));
+    }
+
+    #[test]
+    fn translate_key_functions() {
+        assert_that!(
+            &translate("LT(1, KC_F)", 2),
+            eq(Ok(Some(ControlCode::TapToggle(LayerRef::ByIndex(1), KC_F))))
+        );
+        assert_that!(
+            &translate("MO(1)", 2),
+            eq(Ok(Some(ControlCode::Momentary(LayerRef::ByIndex(1)))))
+        );
+        assert_that!(
+            &translate("TG(1)", 2),
+            eq(Ok(Some(ControlCode::Toggle(LayerRef::ByIndex(1)))))
+        );
+        assert_that!(
+            &translate("TO(0)", 2),
+            eq(Ok(Some(ControlCode::Switch(LayerRef::ByIndex(0)))))
+        );
+        assert_that!(
+            &translate("MT(MOD_LCTL, KC_A)", 2),
+            eq(Ok(Some(ControlCode::ModTap(KC_LEFTCTRL, KC_A))))
+        );
+        assert_that!(&translate("MO(2)", 2), eq(Err("layer index out of range")));
+        assert_that!(&translate("MT(MOD_LCTL | MOD_LSFT, KC_A)", 2), eq(Err("only single-modifier mod-taps are supported")));
+        assert_that!(&translate("OSM(MOD_LSFT)", 2), eq(Err("unsupported key function")));
+    }
+
+    #[test]
+    fn import_layers() {
+        // caps lock as escape, f as a navigation layer tap toggle and an identity map on grave
+        let base = layer_with(vec![(0, "KC_GRV"), (28, "KC_ESC"), (32, "LT(1, KC_F)")]);
+        let nav = layer_with(vec![(34, "KC_LEFT"), (35, "KC_DOWN"), (36, "KC_UP"), (37, "KC_RGHT"), (39, "KC_NO")]);
+
+        let import = import_keymap(&keymap(vec![base, nav])).unwrap();
+        let layers: Vec<&Layer> = import.composer.iter().collect();
+        assert_that!(&layers.len(), eq(2));
+        assert_that!(&layers[0].active, eq(true));
+        assert_that!(&layers[1].active, eq(false));
+
+        let base: HashMap<_, _> = layers[0].iter().map(|(k, v)| (EventCode::KeyCode(k), v.clone())).collect();
+        assert_that!(&base.len(), eq(2));
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_CAPSLOCK)],
+            eq(vec![ControlCode::KeyMap(KC_ESC)])
+        );
+        assert_that!(
+            &base[&EventCode::KeyCode(KC_F)],
+            eq(vec![ControlCode::TapToggle(LayerRef::ByIndex(1), KC_F)])
+        );
+
+        let nav: HashMap<_, _> = layers[1].iter().map(|(k, v)| (EventCode::KeyCode(k), v.clone())).collect();
+        assert_that!(&nav.len(), eq(4));
+        assert_that!(&nav[&EventCode::KeyCode(KC_J)], eq(vec![ControlCode::KeyMap(KC_DOWN)]));
+
+        assert_that!(
+            &import.unsupported,
+            eq(vec![Unsupported {
+                layer: 1,
+                position: 39,
+                keycode: "KC_NO".to_string(),
+                reason: "disabling keys is not supported",
+            }])
+        );
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let base = layer_with(vec![(28, "KC_ESC"), (32, "LT(1, KC_F)"), (29, "MT(MOD_LCTL, KC_A)")]);
+        let nav = layer_with(vec![(34, "KC_LEFT"), (35, "KC_DOWN"), (36, "KC_UP"), (37, "KC_RGHT"), (53, "TG(1)"), (52, "TO(0)")]);
+        let import = import_keymap(&keymap(vec![base, nav])).unwrap();
+
+        let export = export(&import.composer, "LAYOUT_60_ansi").unwrap();
+        assert_that!(&export.warnings.len(), eq(0));
+
+        let layers = &export.keymap.layers;
+        assert_that!(&layers[0][0].as_str(), eq("KC_GRV"));
+        assert_that!(&layers[0][28].as_str(), eq("KC_ESC"));
+        assert_that!(&layers[0][29].as_str(), eq("MT(MOD_LCTL, KC_A)"));
+        assert_that!(&layers[0][32].as_str(), eq("LT(1, KC_F)"));
+        assert_that!(&layers[0][33].as_str(), eq("KC_G"));
+        assert_that!(&layers[1][33].as_str(), eq("KC_TRNS"));
+        assert_that!(&layers[1][37].as_str(), eq("KC_RGHT"));
+        assert_that!(&layers[1][53].as_str(), eq("TG(1)"));
+        assert_that!(&layers[1][52].as_str(), eq("TO(0)"));
+
+        let reimport = import_keymap(&export.keymap).unwrap();
+        assert_that!(&reimport.unsupported.len(), eq(0));
+    }
+
+    #[test]
+    fn export_warnings() {
+        let layers = vec![
+            Layer::from_hashmap(
+                "base".to_string(),
+                maplit::hashmap!(
+                    KC_END => vec![ControlCode::Exit],
+                    KC_F13 => vec![ControlCode::KeyMap(KC_A)],
+                    KC_F => vec![ControlCode::TapToggle(LayerRef::ByName("nav".to_string()), KC_F)],
+                ),
+                true,
+            ),
+            Layer::from_hashmap("nav".to_string(), HashMap::new(), true),
+        ];
+        let lc = LayerComposer::from_layers(layers).unwrap();
+
+        let export = export(&lc, "LAYOUT_tkl_ansi").unwrap();
+        assert_that!(&export.keymap.layers[0][48].as_str(), eq("KC_NO"));
+        assert_that!(&export.keymap.layers[0][54].as_str(), eq("LT(1, KC_F)"));
+
+        let mut messages: Vec<String> = export.warnings.iter().map(|w| w.to_string()).collect();
+        messages.sort();
+        assert_that!(
+            &messages,
+            eq(vec![
+                "layer base key KC_END: Exit has no QMK equivalent".to_string(),
+                "layer base key KC_F13: key is not part of LAYOUT_tkl_ansi".to_string(),
+                "layer nav: only the bottom layer is active at startup in QMK".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn import_layout_mismatch() {
+        let km = keymap(vec![vec!["KC_A"]]);
+        assert!(matches!(
+            import_keymap(&km),
+            Err(Error::LayoutMismatch { expected: 61, found: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
+
+use num::FromPrimitive;
+
+use crate::device::frames::Frame;
+use crate::errors::{Error, Result};
+use crate::events::{EventCode, InputEvent, KeyCode, KeyCode::*, KeyState};
+use crate::formats::kanata;
+
+/// How long the engine waits between injected keystrokes. Applications may miss keys that
+/// arrive in a burst, and live typing gets a chance to go out in between.
+pub const KEYSTROKE_INTERVAL: Duration = Duration::from_millis(8);
+
+const MODIFIERS: [KeyCode; 8] = [
+    KC_LEFTCTRL,
+    KC_RIGHTCTRL,
+    KC_LEFTSHIFT,
+    KC_RIGHTSHIFT,
+    KC_LEFTALT,
+    KC_RIGHTALT,
+    KC_LEFTMETA,
+    KC_RIGHTMETA,
+];
+
+/// Keys pressed together, eg `LCTRL+L`, in the order they are pressed; they are released in
+/// reverse.
+pub type Chord = Vec<KeyCode>;
+
+/// Parses a chord of key names joined by `+`. Names are those of the `remap!` macro, eg
+/// `LEFTCTRL`, with or without the `KC_` prefix, or kanata's abbreviations such as `lctrl`.
+pub fn parse_chord(chord: &str) -> Result<Chord> {
+    chord
+        .split('+')
+        .map(|name| {
+            let lower = name.trim().to_lowercase();
+            let lower = lower.strip_prefix("kc_").unwrap_or(&lower);
+            kanata::keycode_from_name(lower).ok_or_else(|| Error::UnknownKey(name.to_string()))
+        })
+        .collect()
+}
+
+/// Returns the keystrokes that type `text` with a US layout. The virtual device sends key codes
+/// rather than characters, so with another layout active the desktop types other characters.
+pub fn text_chords(text: &str) -> Result<Vec<Chord>> {
+    text.chars()
+        .map(|c| match us_key(c) {
+            Some((kc, false)) => Ok(vec![kc]),
+            Some((kc, true)) => Ok(vec![KC_LEFTSHIFT, kc]),
+            None => Err(Error::UntypeableChar(c)),
+        })
+        .collect()
+}
+
+// Returns the key typing `c` on a US layout and whether shift must be held for it.
+fn us_key(c: char) -> Option<(KeyCode, bool)> {
+    if c.is_ascii_alphanumeric() {
+        let kc = kanata::keycode_from_name(&c.to_ascii_lowercase().to_string())?;
+        return Some((kc, c.is_ascii_uppercase()));
+    }
+    let key = match c {
+        ' ' => (KC_SPACE, false),
+        '\n' => (KC_ENTER, false),
+        '\t' => (KC_TAB, false),
+        '-' => (KC_MINUS, false),
+        '=' => (KC_EQUAL, false),
+        '[' => (KC_LEFTBRACE, false),
+        ']' => (KC_RIGHTBRACE, false),
+        '\\' => (KC_BACKSLASH, false),
+        ';' => (KC_SEMICOLON, false),
+        '\'' => (KC_APOSTROPHE, false),
+        '`' => (KC_GRAVE, false),
+        ',' => (KC_COMMA, false),
+        '.' => (KC_DOT, false),
+        '/' => (KC_SLASH, false),
+        '!' => (KC_1, true),
+        '@' => (KC_2, true),
+        '#' => (KC_3, true),
+        '$' => (KC_4, true),
+        '%' => (KC_5, true),
+        '^' => (KC_6, true),
+        '&' => (KC_7, true),
+        '*' => (KC_8, true),
+        '(' => (KC_9, true),
+        ')' => (KC_0, true),
+        '_' => (KC_MINUS, true),
+        '+' => (KC_EQUAL, true),
+        '{' => (KC_LEFTBRACE, true),
+        '}' => (KC_RIGHTBRACE, true),
+        '|' => (KC_BACKSLASH, true),
+        ':' => (KC_SEMICOLON, true),
+        '"' => (KC_APOSTROPHE, true),
+        '~' => (KC_GRAVE, true),
+        '<' => (KC_COMMA, true),
+        '>' => (KC_DOT, true),
+        '?' => (KC_SLASH, true),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Injector writes keystrokes requested over the control socket to the virtual device, one
+/// every `KEYSTROKE_INTERVAL`. It keeps track of the keys held on the virtual device so that
+/// keystrokes come out the same whatever the user is holding: modifiers that aren't part of a
+/// keystroke are lifted for its duration, and keys already held aren't pressed or released.
+pub struct Injector {
+    pending: VecDeque<Chord>,
+    held: Vec<bool>,
+    next: Instant,
+}
+
+impl Default for Injector {
+    fn default() -> Self {
+        Injector {
+            pending: VecDeque::new(),
+            held: vec![false; KeyCode::COUNT],
+            next: Instant::now(),
+        }
+    }
+}
+
+impl Injector {
+    pub fn queue(&mut self, chords: Vec<Chord>) {
+        self.pending.extend(chords);
+    }
+
+    /// Records the keys held on the virtual device after `frame` was written to it.
+    pub fn track(&mut self, frame: &[InputEvent]) {
+        for e in frame {
+            if let EventCode::KeyCode(kc) = e.code {
+                self.held[kc as usize] = e.state != KeyState::Up;
+            }
+        }
+    }
+
+    /// Whether `kc` is held on the virtual device.
+    pub fn is_held(&self, kc: KeyCode) -> bool {
+        self.held[kc as usize]
+    }
+
+    /// Returns a frame releasing every key held on the virtual device, or None if none are.
+    pub fn release_all(&self) -> Option<Frame> {
+        let time = SystemTime::now();
+        let frame: Frame = (0..self.held.len())
+            .filter(|i| self.held[*i])
+            .filter_map(KeyCode::from_usize)
+            .map(|kc| key_event(kc, KeyState::Up, time))
+            .collect();
+        Some(frame).filter(|frame| !frame.is_empty())
+    }
+
+    /// Returns how long until the next keystroke is due, or None if there are none.
+    pub fn interval(&self, now: Instant) -> Option<Duration> {
+        match self.pending.is_empty() {
+            true => None,
+            false => Some(self.next.saturating_duration_since(now)),
+        }
+    }
+
+    /// Returns the frames of the next keystroke if it is due.
+    pub fn next_keystroke(&mut self, now: Instant) -> Option<Vec<Frame>> {
+        if now < self.next {
+            return None;
+        }
+        let chord = self.pending.pop_front()?;
+        self.next = now + KEYSTROKE_INTERVAL;
+        Some(self.keystroke(&chord))
+    }
+
+    fn keystroke(&self, chord: &Chord) -> Vec<Frame> {
+        let time = SystemTime::now();
+        let event = |kc: KeyCode, state: KeyState| key_event(kc, state, time);
+        // eg a held shift would otherwise capitalize typed text
+        let lifted: Vec<KeyCode> = MODIFIERS
+            .iter()
+            .copied()
+            .filter(|m| self.is_held(*m) && !chord.contains(m))
+            .collect();
+        let pressed: Vec<KeyCode> = chord.iter().copied().filter(|kc| !self.is_held(*kc)).collect();
+
+        let mut frames = Vec::new();
+        if !lifted.is_empty() {
+            frames.push(lifted.iter().map(|m| event(*m, KeyState::Up)).collect());
+        }
+        frames.extend(pressed.iter().map(|kc| vec![event(*kc, KeyState::Down)]));
+        frames.extend(pressed.iter().rev().map(|kc| vec![event(*kc, KeyState::Up)]));
+        if !lifted.is_empty() {
+            frames.push(lifted.iter().map(|m| event(*m, KeyState::Down)).collect());
+        }
+        frames
+    }
+}
+
+fn key_event(kc: KeyCode, state: KeyState, time: SystemTime) -> InputEvent {
+    InputEvent {
+        time,
+        code: EventCode::KeyCode(kc),
+        state,
+        value: state as i32,
+        device: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    // The key codes and states of frames, which is what matters about them here.
+    fn keys(frames: &[Frame]) -> Vec<Vec<(KeyCode, KeyState)>> {
+        frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .iter()
+                    .map(|e| match e.code {
+                        EventCode::KeyCode(kc) => (kc, e.state),
+                        _ => panic!("unexpected event {:?}", e),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chords_parse_from_key_names() {
+        assert_that!(&parse_chord("LCTRL+L").unwrap(), eq(vec![KC_LEFTCTRL, KC_L]));
+        assert_that!(&parse_chord("KC_LEFTMETA+enter").unwrap(), eq(vec![KC_LEFTMETA, KC_ENTER]));
+        assert_that!(&parse_chord("F5").unwrap(), eq(vec![KC_F5]));
+        match parse_chord("LCTRL+NOPE") {
+            Err(Error::UnknownKey(name)) => assert_that!(&name.as_str(), eq("NOPE")),
+            other => panic!("expected an unknown key, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn text_is_typed_with_a_us_layout() {
+        let chords = text_chords("Hi 5!\n").unwrap();
+        assert_that!(
+            &chords,
+            eq(vec![
+                vec![KC_LEFTSHIFT, KC_H],
+                vec![KC_I],
+                vec![KC_SPACE],
+                vec![KC_5],
+                vec![KC_LEFTSHIFT, KC_1],
+                vec![KC_ENTER],
+            ])
+        );
+        match text_chords("café") {
+            Err(Error::UntypeableChar(c)) => assert_that!(&c, eq('é')),
+            other => panic!("expected an untypeable character, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keystrokes_are_spaced_out() {
+        let mut injector = Injector::default();
+        let now = Instant::now();
+        assert_that!(&injector.interval(now), eq(None));
+
+        injector.queue(vec![vec![KC_A], vec![KC_B]]);
+        assert_that!(&injector.interval(now), eq(Some(Duration::ZERO)));
+        let frames = injector.next_keystroke(now).unwrap();
+        assert_that!(
+            &keys(&frames),
+            eq(vec![vec![(KC_A, KeyState::Down)], vec![(KC_A, KeyState::Up)]])
+        );
+
+        assert_that!(&injector.interval(now), eq(Some(KEYSTROKE_INTERVAL)));
+        assert!(injector.next_keystroke(now).is_none());
+        assert!(injector.next_keystroke(now + KEYSTROKE_INTERVAL).is_some());
+        assert_that!(&injector.interval(now), eq(None));
+    }
+
+    fn held(kc: KeyCode) -> InputEvent {
+        key_event(kc, KeyState::Down, SystemTime::now())
+    }
+
+    #[test]
+    fn keystrokes_respect_held_keys() {
+        let mut injector = Injector::default();
+        injector.track(&[held(KC_LEFTSHIFT), held(KC_LEFTCTRL)]);
+
+        // the held shift is lifted so that it doesn't change the keystroke, and the held control
+        // is neither pressed nor released
+        injector.queue(vec![vec![KC_LEFTCTRL, KC_L]]);
+        let frames = injector.next_keystroke(Instant::now()).unwrap();
+        assert_that!(
+            &keys(&frames),
+            eq(vec![
+                vec![(KC_LEFTSHIFT, KeyState::Up)],
+                vec![(KC_L, KeyState::Down)],
+                vec![(KC_L, KeyState::Up)],
+                vec![(KC_LEFTSHIFT, KeyState::Down)],
+            ])
+        );
+    }
+
+    #[test]
+    fn held_keys_are_released() {
+        let mut injector = Injector::default();
+        assert!(injector.release_all().is_none());
+
+        injector.track(&[held(KC_LEFTSHIFT), held(KC_A)]);
+        injector.track(&[key_event(KC_A, KeyState::Up, SystemTime::now())]);
+        injector.track(&[held(KC_B)]);
+        let release = injector.release_all().unwrap();
+        assert_that!(
+            &keys(&[release]),
+            eq(vec![vec![(KC_LEFTSHIFT, KeyState::Up), (KC_B, KeyState::Up)]])
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+
+use hdrhistogram::Histogram;
+use log::{debug, log_enabled, Level};
+
+/// How often the latency recorded so far is logged at debug level.
+pub const LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+// Latencies are recorded in microseconds; anything longer than this is recorded as this.
+const HIGHEST: u64 = 60_000_000;
+
+/// The stages of a frame's way through the engine, each of which gets a histogram of its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// From the kernel timestamping the frame's events to a reader thread having read them.
+    Read,
+    /// From being read to the handler starting to transform the frame, ie time spent queued.
+    Queue,
+    /// Transforming the frame.
+    Transform,
+    /// From the end of the transform to the output having been written to the virtual device.
+    Write,
+    /// From the kernel timestamp to the output having been written.
+    Total,
+}
+
+const STAGES: [Stage; 5] = [Stage::Read, Stage::Queue, Stage::Transform, Stage::Write, Stage::Total];
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Read => "read",
+            Stage::Queue => "queue",
+            Stage::Transform => "transform",
+            Stage::Write => "write",
+            Stage::Total => "total",
+        };
+        f.pad(name)
+    }
+}
+
+/// When a frame passed each stage. Stamps travel with a frame through the engine's channels; the
+/// output of a frame carries the stamps of the input it was transformed from.
+#[derive(Clone, Copy, Debug)]
+pub struct Stamps {
+    kernel: SystemTime,
+    // the kernel stamps events on the wall clock, so the time they are read is taken on both
+    read_wall: SystemTime,
+    read: Instant,
+    transform_start: Instant,
+    transform_end: Instant,
+}
+
+impl Stamps {
+    /// Stamps a frame as read just now; `kernel` is the timestamp of its events.
+    pub fn read(kernel: SystemTime) -> Stamps {
+        let read = Instant::now();
+        Stamps {
+            kernel,
+            read_wall: SystemTime::now(),
+            read,
+            transform_start: read,
+            transform_end: read,
+        }
+    }
+
+    pub fn transform_started(&mut self) {
+        self.transform_start = Instant::now();
+    }
+
+    pub fn transform_ended(&mut self) {
+        self.transform_end = Instant::now();
+    }
+
+    // None if the wall clock was stepped back between the kernel's timestamp and the read.
+    fn kernel_to_read(&self) -> Option<Duration> {
+        self.read_wall.duration_since(self.kernel).ok()
+    }
+}
+
+/// Latency keeps a histogram of how long frames spend in each stage. Reader threads only stamp
+/// the frames they read; the handler thread records every stage once a frame has been written.
+/// Clones share the same histograms, so the caller can report what the handler recorded.
+#[derive(Clone)]
+pub struct Latency {
+    inner: Arc<Mutex<Histograms>>,
+}
+
+struct Histograms {
+    stages: Vec<Histogram<u64>>,
+    logged: Instant,
+}
+
+impl Histograms {
+    fn record(&mut self, stage: Stage, duration: Duration) {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.stages[stage as usize].saturating_record(micros);
+    }
+}
+
+impl Default for Latency {
+    fn default() -> Self {
+        Latency::new()
+    }
+}
+
+impl Latency {
+    pub fn new() -> Latency {
+        let histogram = Histogram::new_with_bounds(1, HIGHEST, 3).expect("histogram bounds are valid");
+        Latency {
+            inner: Arc::new(Mutex::new(Histograms {
+                stages: vec![histogram; STAGES.len()],
+                logged: Instant::now(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Histograms> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Records the stages up to the end of the transform, which every frame read passes through
+    /// whether or not it produces output.
+    pub fn transformed(&self, stamps: &Stamps) {
+        let mut histograms = self.lock();
+        if let Some(read) = stamps.kernel_to_read() {
+            histograms.record(Stage::Read, read);
+        }
+        histograms.record(Stage::Queue, stamps.transform_start - stamps.read);
+        histograms.record(Stage::Transform, stamps.transform_end - stamps.transform_start);
+
+        if log_enabled!(Level::Debug) && histograms.logged.elapsed() >= LOG_INTERVAL {
+            histograms.logged = Instant::now();
+            debug!("latency so far:\n{}", Report(&histograms));
+        }
+    }
+
+    /// Records the remaining stages of a frame whose output was written at `written`.
+    pub fn written(&self, stamps: &Stamps, written: Instant) {
+        let mut histograms = self.lock();
+        let write = written.saturating_duration_since(stamps.transform_end);
+        histograms.record(Stage::Write, write);
+        if let Some(read) = stamps.kernel_to_read() {
+            histograms.record(Stage::Total, read + written.saturating_duration_since(stamps.read));
+        }
+    }
+
+    /// Returns how many frames were recorded for the given stage.
+    pub fn count(&self, stage: Stage) -> u64 {
+        self.lock().stages[stage as usize].len()
+    }
+}
+
+/// Prints a table of the latency percentiles of each stage in microseconds.
+impl fmt::Display for Latency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Report(&self.lock()).fmt(f)
+    }
+}
+
+struct Report<'a>(&'a Histograms);
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<10} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+            "stage (µs)", "count", "p50", "p90", "p99", "p99.9", "max"
+        )?;
+        for stage in STAGES {
+            let h = &self.0.stages[stage as usize];
+            writeln!(
+                f,
+                "{:<10} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                stage,
+                h.len(),
+                h.value_at_quantile(0.5),
+                h.value_at_quantile(0.9),
+                h.value_at_quantile(0.99),
+                h.value_at_quantile(0.999),
+                h.max(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    fn stamps(kernel_to_read: Duration, queued: Duration, transform: Duration) -> Stamps {
+        let read = Instant::now();
+        let read_wall = SystemTime::now();
+        Stamps {
+            kernel: read_wall - kernel_to_read,
+            read_wall,
+            read,
+            transform_start: read + queued,
+            transform_end: read + queued + transform,
+        }
+    }
+
+    #[test]
+    fn every_stage_is_recorded() {
+        let latency = Latency::new();
+        let stamps = stamps(Duration::from_micros(100), Duration::from_micros(20), Duration::from_micros(3));
+        latency.transformed(&stamps);
+        latency.written(&stamps, stamps.transform_end + Duration::from_micros(50));
+
+        let histograms = latency.lock();
+        let max = |stage: Stage| histograms.stages[stage as usize].max();
+        assert_that!(&max(Stage::Read), eq(100));
+        assert_that!(&max(Stage::Queue), eq(20));
+        assert_that!(&max(Stage::Transform), eq(3));
+        assert_that!(&max(Stage::Write), eq(50));
+        assert_that!(&max(Stage::Total), eq(173));
+    }
+
+    #[test]
+    fn frames_without_output_are_not_written() {
+        let latency = Latency::new();
+        latency.transformed(&stamps(Duration::ZERO, Duration::ZERO, Duration::ZERO));
+
+        assert_that!(&latency.count(Stage::Transform), eq(1));
+        assert_that!(&latency.count(Stage::Write), eq(0));
+        assert_that!(&latency.count(Stage::Total), eq(0));
+    }
+
+    #[test]
+    fn wall_clock_steps_skip_the_read_stage() {
+        let latency = Latency::new();
+        let mut stamps = stamps(Duration::ZERO, Duration::ZERO, Duration::ZERO);
+        stamps.kernel = stamps.read_wall + Duration::from_secs(1);
+        latency.transformed(&stamps);
+        latency.written(&stamps, stamps.transform_end);
+
+        assert_that!(&latency.count(Stage::Read), eq(0));
+        assert_that!(&latency.count(Stage::Queue), eq(1));
+        assert_that!(&latency.count(Stage::Total), eq(0));
+    }
+
+    #[test]
+    fn report_lists_every_stage() {
+        let latency = Latency::new();
+        let report = latency.to_string();
+        let stages: Vec<&str> = report
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_whitespace().next())
+            .collect();
+        assert_that!(&stages, eq(vec!["read", "queue", "transform", "write", "total"]));
+    }
+}
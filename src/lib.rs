@@ -1,11 +1,17 @@
 pub mod device;
 mod cli;
+pub mod control;
 pub mod engine;
 pub mod errors;
 pub mod events;
+pub mod formats;
+pub mod inject;
+pub mod latency;
 pub mod layers;
 pub mod listener;
+pub mod realtime;
 pub mod recorder;
+pub mod signals;
 
 mod entrypoint;
 
@@ -4,14 +4,14 @@ use log::trace;
 use crate::events::EventCode;
 use crate::device::traits::InputEventSource;
 
-use crate::device::linux::Device;
+use crate::device::PlatformDevice;
 
 pub struct StdoutListener {
-    d: Device,
+    d: PlatformDevice,
 }
 
 impl StdoutListener {
-    pub fn from_device(d: Device) -> Self {
+    pub fn from_device(d: PlatformDevice) -> Self {
         StdoutListener { d }
     }
 
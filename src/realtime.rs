@@ -0,0 +1,82 @@
+use std::hint::black_box;
+use std::io;
+
+use log::{info, warn};
+
+/// The `SCHED_FIFO` priority of the threads events pass through. It is below the priority
+/// `PREEMPT_RT` kernels give interrupt threads, so that the interrupts of the very devices being
+/// read are still handled first.
+pub const DEFAULT_PRIORITY: i32 = 40;
+
+// How much of a thread's stack is touched up front, well more than the hot path uses.
+const STACK_PREFAULT: usize = 256 * 1024;
+
+/// Realtime moves the threads events pass through, from being read to being written, ahead of
+/// ordinary processes so that keys don't lag under load. Every step degrades gracefully: without
+/// the necessary privileges (`CAP_SYS_NICE` and `CAP_IPC_LOCK`, or `RLIMIT_RTPRIO` and
+/// `RLIMIT_MEMLOCK` raised), a warning is logged and remapping carries on as usual.
+#[derive(Clone, Copy, Debug)]
+pub struct Realtime {
+    pub priority: i32,
+}
+
+impl Default for Realtime {
+    fn default() -> Self {
+        Realtime {
+            priority: DEFAULT_PRIORITY,
+        }
+    }
+}
+
+impl Realtime {
+    /// Locks the memory of the process, current and future, so that the hot path never waits
+    /// for pages to be faulted in or swapped back. Threads spawned afterwards get their stacks
+    /// locked as well, which is why this is done before any of them are.
+    pub fn lock_memory(&self) {
+        match unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) } {
+            0 => info!("locked memory"),
+            _ => warn!(
+                "could not lock memory, it may be paged out: {}",
+                io::Error::last_os_error()
+            ),
+        }
+    }
+
+    /// Gives the calling thread `SCHED_FIFO` priority and faults in its stack.
+    pub fn enter(&self) {
+        let param = libc::sched_param {
+            sched_priority: self.priority,
+        };
+        // on Linux the scheduling policy is per thread, and 0 is the calling thread
+        match unsafe { libc::sched_setscheduler(0, libc::SCHED_FIFO, &param) } {
+            0 => info!("running with SCHED_FIFO priority {}", self.priority),
+            _ => warn!(
+                "could not set SCHED_FIFO priority {}, running with normal priority: {}",
+                self.priority,
+                io::Error::last_os_error()
+            ),
+        }
+        prefault_stack();
+    }
+}
+
+// Touches the stack down to `STACK_PREFAULT` bytes so that its pages are mapped before the first
+// event arrives rather than while handling it.
+#[inline(never)]
+fn prefault_stack() {
+    black_box([0u8; STACK_PREFAULT]);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    // Tests don't run with the privileges realtime scheduling needs, which must not be fatal.
+    #[test]
+    fn missing_privileges_are_not_fatal() {
+        let realtime = Realtime::default();
+        thread::spawn(move || realtime.enter()).join().unwrap();
+    }
+}
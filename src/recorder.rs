@@ -1,6 +1,7 @@
 use std::fs::File;
 use std::io::LineWriter;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use async_std::channel::unbounded;
 use async_std::channel::Receiver;
@@ -15,8 +16,9 @@ use serde::{
 };
 use serde_json;
 
-use qsk_types::control_code::ControlCode;
-use qsk_types::layer_composer::InputTransformer;
+use qsk_types::control_code::{ControlCode, LayerRef};
+use qsk_types::layer_composer::{InputTransformer, LayerChange};
+use qsk_types::layers::{LayerInfo, LayerSet};
 use crate::errors::Result;
 use crate::events::InputEvent;
 
@@ -63,21 +65,53 @@ impl Listener {
         block_on(self.sender.send(le))?;
         Ok(())
     }
+
+    fn log_output(&mut self, output: &[ControlCode]) {
+        for cc in output {
+            if let Err(e) = self.send(Log::Out(cc.clone())) {
+                error!("error sending: {:?}", e);
+            }
+        }
+    }
 }
 
 impl InputTransformer for Listener {
-    fn transform(&mut self, ie: InputEvent) -> Option<Vec<ControlCode>> {
+    fn transform(&mut self, ie: InputEvent, output: &mut Vec<ControlCode>) {
         if let Err(e) = self.send(Log::In(ie)) {
             error!("error sending: {:?}", e);
         }
-        if let Some(vcc) = self.inner.transform(ie) {
-            for cc in vcc.iter() {
-                if let Err(e) = self.send(Log::Out(cc.clone())) {
-                    error!("error sending: {:?}", e);
-                }
-            }
-            return Some(vcc);
-        }
-        None
+        let start = output.len();
+        self.inner.transform(ie, output);
+        self.log_output(&output[start..]);
+    }
+
+    fn tick(&mut self, output: &mut Vec<ControlCode>) {
+        let start = output.len();
+        self.inner.tick(output);
+        self.log_output(&output[start..]);
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        self.inner.tick_interval()
+    }
+
+    fn layers(&self) -> Vec<LayerInfo> {
+        self.inner.layers()
+    }
+
+    fn active_layers(&self) -> LayerSet {
+        self.inner.active_layers()
+    }
+
+    fn change_layer(
+        &mut self,
+        layer: &LayerRef,
+        change: LayerChange,
+    ) -> qsk_types::errors::Result<bool> {
+        self.inner.change_layer(layer, change)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
     }
 }
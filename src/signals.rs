@@ -0,0 +1,128 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::thread::{self, JoinHandle};
+
+use log::{debug, error, info};
+
+use crate::control::{Request, Response};
+use crate::device::cancel::Cancel;
+use crate::errors::Result;
+
+// The write end of the pipe of the installed `Signals`, or -1. A signal handler can't do much
+// more than write to a file descriptor, so it is kept where the handler can find it.
+static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn on_signal(_: libc::c_int) {
+    let fd = PIPE.load(Ordering::Relaxed);
+    if fd < 0 {
+        return;
+    }
+    // the interrupted code may be about to read errno
+    unsafe {
+        let errno = *libc::__errno_location();
+        libc::write(fd, [0u8].as_ptr() as *const libc::c_void, 1);
+        *libc::__errno_location() = errno;
+    }
+}
+
+/// Signals turns `SIGUSR1` into `Request::TogglePassthrough`, for hotkey daemons and the like
+/// that would rather send a signal than talk to the control socket. The handler only writes to
+/// a pipe; a thread reads from it and sends the request to the engine. The signal's default
+/// disposition, which terminates the process, is restored once it is dropped.
+pub struct Signals {
+    read: File,
+    write: File,
+}
+
+impl Signals {
+    pub fn install() -> Result<Signals> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) } == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let (read, write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+        PIPE.store(write.as_raw_fd(), Ordering::Relaxed);
+        set_handler(on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t)?;
+        info!("SIGUSR1 toggles passthrough");
+        Ok(Signals { read, write })
+    }
+
+    /// Waits for signals on a thread of its own until `cancel` is cancelled.
+    pub fn serve<T>(mut self, requests: Sender<T>, cancel: Cancel) -> Result<JoinHandle<()>>
+    where
+        T: From<(Request, Sender<Response>)> + Send + 'static,
+    {
+        let thread = thread::Builder::new()
+            .name("signals".to_string())
+            .spawn(move || loop {
+                if cancel.wait(self.read.as_raw_fd()).is_err() {
+                    break;
+                }
+                // signals that arrive together toggle once
+                let mut buf = [0u8; 64];
+                while matches!(self.read.read(&mut buf), Ok(n) if n > 0) {}
+                debug!("received SIGUSR1");
+                // nobody waits for the response
+                let (sender, _) = channel();
+                if requests.send((Request::TogglePassthrough, sender).into()).is_err() {
+                    break;
+                }
+            })?;
+        Ok(thread)
+    }
+}
+
+impl Drop for Signals {
+    fn drop(&mut self) {
+        if let Err(err) = set_handler(libc::SIG_DFL) {
+            error!("error restoring SIGUSR1: {:?}", err);
+        }
+        // the handler must not write to the descriptor once it is closed and maybe reused
+        if PIPE.load(Ordering::Relaxed) == self.write.as_raw_fd() {
+            PIPE.store(-1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn set_handler(handler: libc::sighandler_t) -> Result<()> {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handler;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+        if libc::sigaction(libc::SIGUSR1, &action, std::ptr::null_mut()) == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use galvanic_assert::matchers::*;
+    use galvanic_assert::*;
+
+    use super::*;
+
+    #[test]
+    fn sigusr1_toggles_passthrough() -> Result<()> {
+        type Message = (Request, Sender<Response>);
+        let signals = Signals::install()?;
+        let cancel = Cancel::new()?;
+        let (requests, received) = channel::<Message>();
+        let thread = signals.serve(requests, cancel.clone())?;
+
+        unsafe { libc::raise(libc::SIGUSR1) };
+        let (request, _) = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_that!(&request, eq(Request::TogglePassthrough));
+
+        cancel.cancel();
+        thread.join().unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#ifdef __linux__
+#include "linux/uinput.h"
+#elif __FreeBSD__
+#include "freebsd/uinput.h"
+#endif
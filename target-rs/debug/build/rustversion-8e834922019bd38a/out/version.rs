@@ -0,0 +1,5 @@
+crate::version::Version {
+    minor: 95,
+    patch: 0,
+    channel: crate::version::Channel::Stable,
+}